@@ -2,6 +2,8 @@ extern crate embla;
 
 use std::f32;
 
+use embla::input::Input;
+
 fn main() {
     embla::init(|context| {
         let mut phase_l = 0.0;
@@ -22,7 +24,7 @@ fn main() {
             }
         });
 
-        move |_dt, _input| {
+        move |_dt, _input: &Input| {
             // hold on to the audio output for the duration of the application
             let _audio_output = &output;
 
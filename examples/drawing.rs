@@ -7,6 +7,7 @@ use failure::Error;
 
 use embla::assets::image_from_png;
 use embla::graphics::{TextureAtlas, TextureImage};
+use embla::input::Input;
 use embla::math::Vec2;
 use embla::rand;
 use embla::rendering::{Texture, TextureFiltering, Uniform, Vertex, VertexAttributeType};
@@ -30,7 +31,7 @@ fn main() {
 
         let mut atlas = TextureAtlas::new((4096, 4096));
         let texture_size = (4096, 4096);
-        let (program, atlas_texture, vertex_buffer) = {
+        let (mut program, atlas_texture, vertex_buffer) = {
             let renderer = window.renderer();
 
             let texture = renderer
@@ -40,16 +41,15 @@ fn main() {
                 .create_program::<TexturedVertex>(VERTEX_SHADER, FRAGMENT_SHADER)
                 .unwrap();
 
-            let screen_size = renderer.screen_size();
-            program.set_uniform(
-                "screen_size",
-                Uniform::Vec2((screen_size.0 as f32, screen_size.1 as f32)),
-            );
-            program.set_uniform(
-                "texture_size",
-                Uniform::Vec2((texture_size.0 as f32, texture_size.1 as f32)),
-            );
-            program.set_uniform("texture", Uniform::Texture(texture.clone()));
+            program
+                .set_uniform(
+                    "texture_size",
+                    Uniform::Vec2((texture_size.0 as f32, texture_size.1 as f32)),
+                )
+                .unwrap();
+            program
+                .set_uniform("texture", Uniform::Texture(texture.clone()))
+                .unwrap();
 
             let vertex_buffer = renderer.create_vertex_buffer().unwrap();
 
@@ -67,7 +67,7 @@ fn main() {
             })
             .collect();
 
-        move |dt, _input| {
+        move |dt, _input: &Input| {
             let mut vertices = Vec::new();
 
             for (p, v) in logos.iter_mut() {
@@ -86,6 +86,11 @@ fn main() {
                 draw_texture(&mut vertices, &mut atlas, &atlas_texture, &logo, *p).unwrap();
             }
 
+            // positions are in logical units, the window can be resized or moved to a display
+            // with another scale factor at any time
+            let size = window.size();
+            program.set_uniform("screen_size", Uniform::Vec2((size.x as f32, size.y as f32)))?;
+
             let renderer = window.renderer();
             renderer.clear(Some((0.0, 0.0, 0.0, 1.0)));
             renderer
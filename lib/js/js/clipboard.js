@@ -0,0 +1,45 @@
+var clipboard_handler = null;
+
+window.set_clipboard_handler = function (handler) {
+    if (clipboard_handler != null) {
+        clipboard_handler.free();
+    }
+    clipboard_handler = handler;
+}
+
+function clipboard_text(text) {
+    if (clipboard_handler != null && text != null) {
+        clipboard_handler.text(text);
+    }
+}
+
+// pasting something other than text has no text/plain data
+document.addEventListener("paste", function (e) {
+    var text = e.clipboardData.getData("text/plain");
+    if (clipboard_handler != null && text != "") {
+        clipboard_handler.paste(e.timeStamp, text);
+    }
+});
+// copying with nothing selected leaves the clipboard as it was
+document.addEventListener("copy", function () {
+    var text = document.getSelection().toString();
+    if (text != "") {
+        clipboard_text(text);
+    }
+});
+
+// reading needs permission and a focused page, and may show a prompt or paste popup, so it is only
+// done when the app asks for it. Failed reads keep the last known text.
+window.read_clipboard = function () {
+    if (navigator.clipboard == null || navigator.clipboard.readText == null) {
+        return;
+    }
+    navigator.clipboard.readText().then(clipboard_text, function () {});
+}
+
+window.write_clipboard = function (text) {
+    if (navigator.clipboard == null || navigator.clipboard.writeText == null) {
+        return;
+    }
+    navigator.clipboard.writeText(text).catch(function () {});
+}
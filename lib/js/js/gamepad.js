@@ -0,0 +1,55 @@
+var gamepad_handler = null;
+// state of each connected gamepad as of the last poll, by gamepad index
+var gamepad_states = {};
+
+window.set_gamepad_handler = function (handler) {
+    if (gamepad_handler != null) {
+        gamepad_handler.free();
+    }
+    gamepad_handler = handler;
+}
+
+// the Gamepad API has no input events, gamepads are diffed against the last poll instead
+window.poll_gamepads = function () {
+    if (gamepad_handler == null || navigator.getGamepads == null) {
+        return;
+    }
+
+    var connected = {};
+    for (const gamepad of navigator.getGamepads()) {
+        if (gamepad == null || !gamepad.connected) {
+            continue;
+        }
+        connected[gamepad.index] = true;
+
+        var state = gamepad_states[gamepad.index];
+        if (state == null) {
+            state = { pressed: [], values: [], axes: [] };
+            gamepad_states[gamepad.index] = state;
+            gamepad_handler.connected(gamepad.index);
+        }
+        gamepad.buttons.forEach(function (button, i) {
+            if (button.pressed != (state.pressed[i] || false)) {
+                state.pressed[i] = button.pressed;
+                gamepad_handler.button(gamepad.index, i, button.pressed);
+            }
+            if (button.value != (state.values[i] || 0)) {
+                state.values[i] = button.value;
+                gamepad_handler.button_value(gamepad.index, i, button.value);
+            }
+        });
+        gamepad.axes.forEach(function (value, i) {
+            if (value != (state.axes[i] || 0)) {
+                state.axes[i] = value;
+                gamepad_handler.axis(gamepad.index, i, value);
+            }
+        });
+    }
+
+    for (const index of Object.keys(gamepad_states)) {
+        if (!connected[index]) {
+            delete gamepad_states[index];
+            gamepad_handler.disconnected(Number(index));
+        }
+    }
+}
@@ -1,11 +1,18 @@
 window.set_main_loop = function (cb) {
     function runner() {
-        cb.call();
-        window.requestAnimationFrame(runner);
+        if (cb.call()) {
+            window.requestAnimationFrame(runner);
+        } else {
+            cb.free();
+        }
     }
     window.requestAnimationFrame(runner);
 }
 
 window.rand = function () {
     return Math.random();
+}
+
+window.performance_now = function () {
+    return performance.now();
 }
\ No newline at end of file
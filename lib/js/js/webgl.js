@@ -7,18 +7,43 @@ window.gl_set_current_context = function (context) {
 window.gl_enable = function (capability) {
     gl_global.enable(capability);
 }
+window.gl_disable = function (capability) {
+    gl_global.disable(capability);
+}
 window.gl_blend_func = function (sfactor, dfactor) {
     gl_global.blendFunc(sfactor, dfactor);
 }
+window.gl_blend_func_separate = function (src_rgb, dst_rgb, src_alpha, dst_alpha) {
+    gl_global.blendFuncSeparate(src_rgb, dst_rgb, src_alpha, dst_alpha);
+}
+window.gl_blend_equation_separate = function (mode_rgb, mode_alpha) {
+    gl_global.blendEquationSeparate(mode_rgb, mode_alpha);
+}
+window.gl_color_mask = function (r, g, b, a) {
+    gl_global.colorMask(r, g, b, a);
+}
+window.gl_cull_face = function (mode) {
+    gl_global.cullFace(mode);
+}
 window.gl_draw_arrays = function (mode, first, count) {
     gl_global.drawArrays(mode, first, count);
 }
+window.gl_draw_elements = function (mode, count, type, offset) {
+    gl_global.drawElements(mode, count, type, offset);
+}
+// getting the extension is also what enables it on the context
+window.gl_element_index_uint_supported = function () {
+    return gl_global.getExtension("OES_element_index_uint") != null;
+}
 window.gl_clear_color = function (r, g, b, a) {
     gl_global.clearColor(r, g, b, a);
 }
 window.gl_clear = function (mask) {
     gl_global.clear(mask);
 }
+window.gl_viewport = function (x, y, width, height) {
+    gl_global.viewport(x, y, width, height);
+}
 
 window.gl_drawing_buffer_width = function () {
     return gl_global.drawingBufferWidth;
@@ -165,6 +190,12 @@ window.gl_program_info_log_len = function (program) {
 window.gl_get_program_info_log = function (program) {
     return gl_global.getProgramInfoLog(program);
 }
+window.gl_get_active_uniform_name = function (program, index) {
+    return gl_global.getActiveUniform(program, index).name;
+}
+window.gl_get_active_attrib_name = function (program, index) {
+    return gl_global.getActiveAttrib(program, index).name;
+}
 
 window.gl_create_buffer = function () {
     return gl_global.createBuffer();
@@ -182,12 +213,45 @@ window.gl_buffer_data = function (target, data, usage) {
 window.gl_get_uniform_location = function (program, name) {
     return gl_global.getUniformLocation(program, name);
 };
+window.gl_uniform1f = function (location, v0) {
+    gl_global.uniform1f(location, v0);
+}
 window.gl_uniform2f = function (location, v0, v1) {
     gl_global.uniform2f(location, v0, v1);
 }
+window.gl_uniform3f = function (location, v0, v1, v2) {
+    gl_global.uniform3f(location, v0, v1, v2);
+}
+window.gl_uniform4f = function (location, v0, v1, v2, v3) {
+    gl_global.uniform4f(location, v0, v1, v2, v3);
+}
 window.gl_uniform1i = function (location, v0) {
     gl_global.uniform1i(location, v0);
 }
+window.gl_uniform1fv = function (location, values) {
+    gl_global.uniform1fv(location, values);
+}
+window.gl_uniform2fv = function (location, values) {
+    gl_global.uniform2fv(location, values);
+}
+window.gl_uniform3fv = function (location, values) {
+    gl_global.uniform3fv(location, values);
+}
+window.gl_uniform4fv = function (location, values) {
+    gl_global.uniform4fv(location, values);
+}
+window.gl_uniform1iv = function (location, values) {
+    gl_global.uniform1iv(location, values);
+}
+window.gl_uniform_matrix2fv = function (location, transpose, values) {
+    gl_global.uniformMatrix2fv(location, transpose, values);
+}
+window.gl_uniform_matrix3fv = function (location, transpose, values) {
+    gl_global.uniformMatrix3fv(location, transpose, values);
+}
+window.gl_uniform_matrix4fv = function (location, transpose, values) {
+    gl_global.uniformMatrix4fv(location, transpose, values);
+}
 
 window.gl_get_attrib_location = function (program, name) {
     return gl_global.getAttribLocation(program, name);
@@ -1,34 +1,329 @@
+// packs the modifier flags of a keyboard event, see `to_modifiers` in the web backend
+function modifier_bits(event) {
+    return (event.shiftKey ? 1 : 0) | (event.ctrlKey ? 2 : 0) | (event.altKey ? 4 : 0) | (event.metaKey ? 8 : 0);
+}
+
+// touch phases, see `to_touch_phase` in the web backend
+const TOUCH_STARTED = 0;
+const TOUCH_MOVED = 1;
+const TOUCH_ENDED = 2;
+const TOUCH_CANCELLED = 3;
+
 class CanvasWindow {
     constructor(canvas_id, input_handler) {
         this.canvas = document.getElementById(canvas_id);
         this.input_handler = input_handler;
+        this.listeners = [];
+        this.resizable = false;
+        this.scale_factor = window.devicePixelRatio || 1;
+        this.cursor = "";
+        this.cursor_visible = true;
+        this.relative = false;
+        // hidden textarea that receives text and IME composition while text input is active
+        this.text_area = null;
+        this.composing = false;
+        // size to go back to when leaving fullscreen
+        this.windowed_size = null;
+
+        // keyboard events go to the canvas last clicked, starting with the first one created
+        if (CanvasWindow.active == null) {
+            CanvasWindow.active = this;
+        }
 
-        this.canvas.addEventListener("mousemove", function (event) {
-            input_handler.mouse_move(event.offsetX, event.offsetY);
+        var self = this;
+        this.listen(this.canvas, "mousemove", function (event) {
+            input_handler.mouse_move(event.timeStamp, self.physical(event.offsetX), self.physical(event.offsetY));
+            input_handler.mouse_motion(event.timeStamp, self.physical(event.movementX), self.physical(event.movementY));
+        });
+        this.listen(this.canvas, "mousedown", function (event) {
+            CanvasWindow.activate(self, event.timeStamp);
+            // keep focus on the text area instead of moving it to the page
+            if (self.text_area != null) {
+                event.preventDefault();
+            }
+            // pointer lock is only granted from a user gesture, so keep asking on clicks
+            if (self.relative && document.pointerLockElement !== self.canvas) {
+                self.canvas.requestPointerLock();
+            }
+            input_handler.mouse_down(event.timeStamp, event.button, self.physical(event.offsetX), self.physical(event.offsetY));
+        });
+        this.listen(this.canvas, "mouseup", function (event) {
+            input_handler.mouse_up(event.timeStamp, event.button, self.physical(event.offsetX), self.physical(event.offsetY));
+        });
+        // touches arrive as pointer events, the browser's own panning and zooming is turned off
+        // and so are the mouse events it would emulate
+        this.canvas.style.touchAction = "none";
+        var touch_listener = function (phase) {
+            return function (event) {
+                if (event.pointerType == "touch") {
+                    input_handler.touch(event.timeStamp, event.pointerId, phase, self.physical(event.offsetX), self.physical(event.offsetY), event.pressure);
+                    event.preventDefault();
+                }
+            };
+        };
+        this.listen(this.canvas, "pointerdown", touch_listener(TOUCH_STARTED));
+        this.listen(this.canvas, "pointermove", touch_listener(TOUCH_MOVED));
+        this.listen(this.canvas, "pointerup", touch_listener(TOUCH_ENDED));
+        this.listen(this.canvas, "pointercancel", touch_listener(TOUCH_CANCELLED));
+        this.listen(this.canvas, "wheel", function (event) {
+            // wheel deltas come in pixels, lines or pages depending on the browser and device,
+            // one notch is roughly 100 pixels or 3 lines
+            var scale = [1 / 100, 1 / 3, 1][event.deltaMode];
+            input_handler.mouse_wheel(event.timeStamp, event.deltaX * scale, -event.deltaY * scale);
+            event.preventDefault();
+        }, { passive: false });
+        this.listen(window, "keydown", function (event) {
+            if (CanvasWindow.active === self) {
+                input_handler.key_down(event.timeStamp, event.keyCode, event.location, event.code, modifier_bits(event), event.repeat);
+            }
+        });
+        this.listen(window, "keyup", function (event) {
+            if (CanvasWindow.active === self) {
+                input_handler.key_up(event.timeStamp, event.keyCode, event.location, event.code, modifier_bits(event), false);
+            }
         });
-        this.canvas.addEventListener("mousedown", function (event) {
-            input_handler.mouse_down(event.button, event.offsetX, event.offsetY);
+        this.listen(window, "focus", function (event) {
+            if (CanvasWindow.active === self) {
+                input_handler.focus(event.timeStamp, true);
+            }
         });
-        this.canvas.addEventListener("mouseup", function (event) {
-            input_handler.mouse_up(event.button, event.offsetX, event.offsetY);
+        this.listen(window, "blur", function (event) {
+            if (CanvasWindow.active === self) {
+                input_handler.focus(event.timeStamp, false);
+            }
         });
-        window.addEventListener("keydown", function (event) {
-            input_handler.key_down(event.keyCode);
+        this.listen(document, "visibilitychange", function (event) {
+            input_handler.visibility(event.timeStamp, document.visibilityState == "visible");
         });
-        window.addEventListener("keyup", function (event) {
-            input_handler.key_up(event.keyCode);
+        // zooming or moving to another display changes the pixel ratio and fires a resize
+        this.listen(window, "resize", function (event) {
+            var scale_factor = window.devicePixelRatio || 1;
+            if (scale_factor != self.scale_factor) {
+                self.scale_factor = scale_factor;
+                input_handler.scale_factor_changed(event.timeStamp, scale_factor);
+            }
+            self.fit(event.timeStamp);
         });
+        this.listen(document, "fullscreenchange", function (event) {
+            if (document.fullscreenElement === self.canvas) {
+                self.windowed_size = [self.canvas.clientWidth, self.canvas.clientHeight];
+                self.fit(event.timeStamp);
+            } else if (self.windowed_size != null) {
+                var [width, height] = self.windowed_size;
+                self.windowed_size = null;
+                if (self.resizable) {
+                    self.fit(event.timeStamp);
+                } else {
+                    self.set_size(width, height, event.timeStamp);
+                }
+            }
+        });
+    }
+
+    start_text_input() {
+        if (this.text_area == null) {
+            var text_area = document.createElement("textarea");
+            text_area.style.position = "absolute";
+            text_area.style.opacity = 0;
+            text_area.style.width = "1px";
+            text_area.style.height = "1px";
+            text_area.style.pointerEvents = "none";
+            text_area.style.left = this.canvas.offsetLeft + "px";
+            text_area.style.top = this.canvas.offsetTop + "px";
+            this.canvas.parentNode.insertBefore(text_area, this.canvas.nextSibling);
+
+            var self = this;
+            var flush = function (time) {
+                if (text_area.value.length > 0) {
+                    self.input_handler.text_input(time, text_area.value);
+                    text_area.value = "";
+                }
+            };
+            text_area.addEventListener("input", function (event) {
+                if (!self.composing) {
+                    flush(event.timeStamp);
+                }
+            });
+            text_area.addEventListener("compositionstart", function (event) {
+                self.composing = true;
+            });
+            text_area.addEventListener("compositionupdate", function (event) {
+                self.input_handler.text_editing(event.timeStamp, event.data, event.data.length, 0);
+            });
+            text_area.addEventListener("compositionend", function (event) {
+                self.composing = false;
+                self.input_handler.text_editing(event.timeStamp, "", 0, 0);
+                flush(event.timeStamp);
+            });
+            this.text_area = text_area;
+        }
+        this.text_area.focus();
+    }
+
+    stop_text_input() {
+        if (this.text_area != null) {
+            this.text_area.remove();
+            this.text_area = null;
+            this.composing = false;
+        }
+    }
+
+    update_cursor() {
+        this.canvas.style.cursor = this.cursor_visible ? this.cursor : "none";
+    }
+
+    set_relative(relative) {
+        this.relative = relative;
+        if (relative && document.pointerLockElement !== this.canvas) {
+            this.canvas.requestPointerLock();
+        } else if (!relative && document.pointerLockElement === this.canvas) {
+            document.exitPointerLock();
+        }
+    }
+
+    physical(v) {
+        return Math.round(v * this.scale_factor);
+    }
+
+    // match the drawing buffer to the size the canvas is laid out at, in device pixels, `time` is
+    // that of the event causing the resize if there is one
+    fit(time = performance.now()) {
+        var width = this.physical(this.canvas.clientWidth);
+        var height = this.physical(this.canvas.clientHeight);
+        if (this.canvas.width != width || this.canvas.height != height) {
+            this.canvas.width = width;
+            this.canvas.height = height;
+            this.input_handler.resize(time, width, height);
+        }
+    }
+
+    // sizes are in css pixels
+    set_size(width, height, time) {
+        this.canvas.style.width = width + "px";
+        this.canvas.style.height = height + "px";
+        this.fit(time);
+    }
+
+    set_fullscreen(fullscreen) {
+        if (fullscreen && document.fullscreenElement !== this.canvas) {
+            this.canvas.requestFullscreen();
+        } else if (!fullscreen && document.fullscreenElement === this.canvas) {
+            document.exitFullscreen();
+        }
+    }
+
+    static activate(canvas_window, time) {
+        if (CanvasWindow.active !== canvas_window) {
+            if (CanvasWindow.active != null) {
+                CanvasWindow.active.input_handler.focus(time, false);
+            }
+            CanvasWindow.active = canvas_window;
+            canvas_window.input_handler.focus(time, true);
+        }
+    }
+
+    listen(target, type, listener, options) {
+        target.addEventListener(type, listener, options);
+        this.listeners.push([target, type, listener]);
+    }
+
+    destroy() {
+        if (CanvasWindow.active === this) {
+            CanvasWindow.active = null;
+        }
+        this.stop_text_input();
+        for (const [target, type, listener] of this.listeners) {
+            target.removeEventListener(type, listener);
+        }
+        this.listeners = [];
+        this.input_handler.free();
     }
 }
+CanvasWindow.active = null;
 
 window.create_canvas_window = function (canvas_id, input_handler) {
     return new CanvasWindow(canvas_id, input_handler);
 }
 
 window.delete_canvas_window = function (window) {
-    window.input_handler.free();
+    window.destroy();
+}
+
+window.set_canvas_window_size = function (window, width, height) {
+    window.set_size(width, height);
+}
+
+window.set_canvas_window_min_size = function (window, width, height) {
+    window.canvas.style.minWidth = width + "px";
+    window.canvas.style.minHeight = height + "px";
+}
+
+// a resizable canvas takes its size from the page layout, otherwise it is kept at the size set
+window.set_canvas_window_resizable = function (window, resizable) {
+    window.resizable = resizable;
+    if (resizable) {
+        window.canvas.style.width = "";
+        window.canvas.style.height = "";
+    }
+    window.fit();
+}
+
+window.set_canvas_window_fullscreen = function (window, fullscreen) {
+    window.set_fullscreen(fullscreen);
+}
+
+window.set_canvas_window_cursor_visible = function (window, visible) {
+    window.cursor_visible = visible;
+    window.update_cursor();
+}
+
+window.set_canvas_window_relative_mouse_mode = function (window, relative) {
+    window.set_relative(relative);
+}
+
+window.set_canvas_window_cursor = function (window, pixels, width, height, hotspot_x, hotspot_y) {
+    var canvas = document.createElement("canvas");
+    canvas.width = width;
+    canvas.height = height;
+    var image = new ImageData(new Uint8ClampedArray(pixels), width, height);
+    canvas.getContext("2d").putImageData(image, 0, 0);
+    window.cursor = "url(" + canvas.toDataURL() + ") " + hotspot_x + " " + hotspot_y + ", auto";
+    window.update_cursor();
+}
+
+window.reset_canvas_window_cursor = function (window) {
+    window.cursor = "";
+    window.update_cursor();
+}
+
+window.start_canvas_window_text_input = function (window) {
+    window.start_text_input();
+}
+
+window.stop_canvas_window_text_input = function (window) {
+    window.stop_text_input();
+}
+
+window.canvas_window_width = function (window) {
+    return window.canvas.clientWidth;
+}
+
+window.canvas_window_height = function (window) {
+    return window.canvas.clientHeight;
+}
+
+window.canvas_window_drawable_width = function (window) {
+    return window.canvas.width;
+}
+
+window.canvas_window_drawable_height = function (window) {
+    return window.canvas.height;
+}
+
+window.canvas_window_scale_factor = function (window) {
+    return window.scale_factor;
 }
 
 window.get_window_context = function (window) {
     return window.canvas.getContext('webgl');
-}
\ No newline at end of file
+}
@@ -0,0 +1,53 @@
+use wasm_bindgen::prelude::*;
+
+pub fn bootstrap() {
+    use super::eval;
+    eval(include_str!("../js/clipboard.js"));
+}
+
+#[wasm_bindgen]
+extern "C" {
+    pub fn set_clipboard_handler(handler: ClipboardHandler);
+    pub fn read_clipboard();
+    pub fn write_clipboard(text: &str);
+}
+
+type TextCallback = Box<FnMut(String) + 'static>;
+// the paste event's timeStamp in milliseconds, and the pasted text
+type PasteCallback = Box<FnMut(f64, String) + 'static>;
+
+#[wasm_bindgen]
+pub struct ClipboardHandler {
+    text: Option<TextCallback>,
+    paste: Option<PasteCallback>,
+}
+
+#[wasm_bindgen]
+impl ClipboardHandler {
+    pub fn text(&mut self, text: String) {
+        if let Some(ref mut callback) = self.text {
+            (*callback)(text);
+        }
+    }
+    pub fn paste(&mut self, time: f64, text: String) {
+        if let Some(ref mut callback) = self.paste {
+            (*callback)(time, text);
+        }
+    }
+}
+
+impl ClipboardHandler {
+    pub fn new() -> ClipboardHandler {
+        ClipboardHandler {
+            text: None,
+            paste: None,
+        }
+    }
+
+    pub fn set_text<T: FnMut(String) + 'static>(&mut self, f: T) {
+        self.text = Some(Box::new(f));
+    }
+    pub fn set_paste<T: FnMut(f64, String) + 'static>(&mut self, f: T) {
+        self.paste = Some(Box::new(f));
+    }
+}
@@ -0,0 +1,87 @@
+use wasm_bindgen::prelude::*;
+
+pub fn bootstrap() {
+    use super::eval;
+    eval(include_str!("../js/gamepad.js"));
+}
+
+#[wasm_bindgen]
+extern "C" {
+    pub fn set_gamepad_handler(handler: GamepadHandler);
+    pub fn poll_gamepads();
+}
+
+type GamepadIndex = u32;
+type ButtonIndex = u32;
+type AxisIndex = u32;
+
+type ConnectionCallback = Box<FnMut(GamepadIndex) + 'static>;
+type ButtonCallback = Box<FnMut(GamepadIndex, ButtonIndex, bool) + 'static>;
+type ButtonValueCallback = Box<FnMut(GamepadIndex, ButtonIndex, f32) + 'static>;
+type AxisCallback = Box<FnMut(GamepadIndex, AxisIndex, f32) + 'static>;
+
+#[wasm_bindgen]
+pub struct GamepadHandler {
+    connected: Option<ConnectionCallback>,
+    disconnected: Option<ConnectionCallback>,
+    button: Option<ButtonCallback>,
+    button_value: Option<ButtonValueCallback>,
+    axis: Option<AxisCallback>,
+}
+
+#[wasm_bindgen]
+impl GamepadHandler {
+    pub fn connected(&mut self, gamepad: GamepadIndex) {
+        if let Some(ref mut connected) = self.connected {
+            (*connected)(gamepad);
+        }
+    }
+    pub fn disconnected(&mut self, gamepad: GamepadIndex) {
+        if let Some(ref mut disconnected) = self.disconnected {
+            (*disconnected)(gamepad);
+        }
+    }
+    pub fn button(&mut self, gamepad: GamepadIndex, button: ButtonIndex, pressed: bool) {
+        if let Some(ref mut callback) = self.button {
+            (*callback)(gamepad, button, pressed);
+        }
+    }
+    pub fn button_value(&mut self, gamepad: GamepadIndex, button: ButtonIndex, value: f32) {
+        if let Some(ref mut button_value) = self.button_value {
+            (*button_value)(gamepad, button, value);
+        }
+    }
+    pub fn axis(&mut self, gamepad: GamepadIndex, axis: AxisIndex, value: f32) {
+        if let Some(ref mut callback) = self.axis {
+            (*callback)(gamepad, axis, value);
+        }
+    }
+}
+
+impl GamepadHandler {
+    pub fn new() -> GamepadHandler {
+        GamepadHandler {
+            connected: None,
+            disconnected: None,
+            button: None,
+            button_value: None,
+            axis: None,
+        }
+    }
+
+    pub fn set_connected<T: FnMut(GamepadIndex) + 'static>(&mut self, f: T) {
+        self.connected = Some(Box::new(f));
+    }
+    pub fn set_disconnected<T: FnMut(GamepadIndex) + 'static>(&mut self, f: T) {
+        self.disconnected = Some(Box::new(f));
+    }
+    pub fn set_button<T: FnMut(GamepadIndex, ButtonIndex, bool) + 'static>(&mut self, f: T) {
+        self.button = Some(Box::new(f));
+    }
+    pub fn set_button_value<T: FnMut(GamepadIndex, ButtonIndex, f32) + 'static>(&mut self, f: T) {
+        self.button_value = Some(Box::new(f));
+    }
+    pub fn set_axis<T: FnMut(GamepadIndex, AxisIndex, f32) + 'static>(&mut self, f: T) {
+        self.axis = Some(Box::new(f));
+    }
+}
@@ -3,6 +3,8 @@
 extern crate wasm_bindgen;
 
 pub mod audio;
+pub mod clipboard;
+pub mod gamepad;
 pub mod webgl;
 pub mod websocket;
 pub mod window;
@@ -13,18 +15,20 @@ pub fn bootstrap() {
     eval(include_str!("../js/lib.js"));
 
     audio::bootstrap();
+    clipboard::bootstrap();
+    gamepad::bootstrap();
     webgl::bootstrap();
     websocket::bootstrap();
     window::bootstrap();
 }
 
 #[wasm_bindgen]
-pub struct MainLoopCallback(pub Box<FnMut() + 'static>);
+pub struct MainLoopCallback(pub Box<FnMut() -> bool + 'static>);
 
 #[wasm_bindgen]
 impl MainLoopCallback {
-    pub fn call(&mut self) {
-        (*self.0)();
+    pub fn call(&mut self) -> bool {
+        (*self.0)()
     }
 }
 
@@ -38,4 +42,5 @@ extern "C" {
     pub fn eval(s: &str);
     pub fn set_main_loop(cb: MainLoopCallback);
     pub fn rand() -> f32;
+    pub fn performance_now() -> f64;
 }
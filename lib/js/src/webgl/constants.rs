@@ -15,6 +15,7 @@ pub const STENCIL_BUFFER_BIT: GLenum = 0x00000400;
 pub const COLOR_BUFFER_BIT: GLenum = 0x00004000;
 
 pub const BLEND: GLenum = 0x0BE2;
+pub const CULL_FACE: GLenum = 0x0B44;
 pub const DEPTH_TEST: GLenum = 0x0B71;
 pub const DITHER: GLenum = 0x0BD0;
 pub const POLYGON_OFFSET_FILL: GLenum = 0x8037;
@@ -23,6 +24,16 @@ pub const SAMPLE_COVERAGE: GLenum = 0x80A0;
 pub const SCISSOR_TEST: GLenum = 0x0C11;
 pub const STENCIL_TEST: GLenum = 0x0B90;
 
+pub const FRONT: GLenum = 0x0404;
+pub const BACK: GLenum = 0x0405;
+pub const FRONT_AND_BACK: GLenum = 0x0408;
+
+pub const FUNC_ADD: GLenum = 0x8006;
+pub const FUNC_SUBTRACT: GLenum = 0x800A;
+pub const FUNC_REVERSE_SUBTRACT: GLenum = 0x800B;
+
+pub const ZERO: GLenum = 0;
+pub const ONE: GLenum = 1;
 pub const SRC_COLOR: GLenum = 0x0300;
 pub const ONE_MINUS_SRC_COLOR: GLenum = 0x0301;
 pub const SRC_ALPHA: GLenum = 0x0302;
@@ -21,10 +21,23 @@ pub fn bootstrap() {
 #[wasm_bindgen]
 extern "C" {
     pub fn gl_enable(capability: GLenum);
+    pub fn gl_disable(capability: GLenum);
     pub fn gl_blend_func(sfactor: GLenum, dfactor: GLenum);
+    pub fn gl_blend_func_separate(
+        src_rgb: GLenum,
+        dst_rgb: GLenum,
+        src_alpha: GLenum,
+        dst_alpha: GLenum,
+    );
+    pub fn gl_blend_equation_separate(mode_rgb: GLenum, mode_alpha: GLenum);
+    pub fn gl_color_mask(r: GLboolean, g: GLboolean, b: GLboolean, a: GLboolean);
+    pub fn gl_cull_face(mode: GLenum);
     pub fn gl_draw_arrays(mode: GLenum, first: GLint, count: GLsizei);
+    pub fn gl_draw_elements(mode: GLenum, count: GLsizei, index_type: GLenum, offset: GLintptr);
+    pub fn gl_element_index_uint_supported() -> bool;
     pub fn gl_clear_color(r: GLclampf, g: GLclampf, b: GLclampf, a: GLclampf);
     pub fn gl_clear(mask: GLbitfield);
+    pub fn gl_viewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
 
     pub fn gl_drawing_buffer_width() -> i32;
     pub fn gl_drawing_buffer_height() -> i32;
@@ -103,10 +116,23 @@ extern "C" {
     pub fn gl_use_program(program: &JsValue);
     pub fn gl_get_program_parameter(program: &JsValue, pname: GLenum) -> GLint;
     pub fn gl_get_program_info_log(program: &JsValue) -> String;
+    pub fn gl_get_active_uniform_name(program: &JsValue, index: GLuint) -> String;
+    pub fn gl_get_active_attrib_name(program: &JsValue, index: GLuint) -> String;
 
     pub fn gl_get_uniform_location(program: &JsValue, name: &str) -> JsValue;
+    pub fn gl_uniform1f(location: &JsValue, v0: GLfloat);
     pub fn gl_uniform2f(location: &JsValue, v0: GLfloat, v1: GLfloat);
+    pub fn gl_uniform3f(location: &JsValue, v0: GLfloat, v1: GLfloat, v2: GLfloat);
+    pub fn gl_uniform4f(location: &JsValue, v0: GLfloat, v1: GLfloat, v2: GLfloat, v3: GLfloat);
     pub fn gl_uniform1i(location: &JsValue, v0: GLint);
+    pub fn gl_uniform1fv(location: &JsValue, values: &[GLfloat]);
+    pub fn gl_uniform2fv(location: &JsValue, values: &[GLfloat]);
+    pub fn gl_uniform3fv(location: &JsValue, values: &[GLfloat]);
+    pub fn gl_uniform4fv(location: &JsValue, values: &[GLfloat]);
+    pub fn gl_uniform1iv(location: &JsValue, values: &[GLint]);
+    pub fn gl_uniform_matrix2fv(location: &JsValue, transpose: GLboolean, values: &[GLfloat]);
+    pub fn gl_uniform_matrix3fv(location: &JsValue, transpose: GLboolean, values: &[GLfloat]);
+    pub fn gl_uniform_matrix4fv(location: &JsValue, transpose: GLboolean, values: &[GLfloat]);
 
     pub fn gl_create_buffer() -> JsValue;
     pub fn gl_delete_buffer(buffer: &JsValue);
@@ -11,54 +11,169 @@ pub type CanvasWindow = JsValue;
 #[wasm_bindgen]
 extern "C" {
     pub fn create_canvas_window(canvas_id: &str, input_handler: InputHandler) -> CanvasWindow;
+    pub fn delete_canvas_window(window: &CanvasWindow);
+
+    pub fn set_canvas_window_size(window: &CanvasWindow, width: u32, height: u32);
+    pub fn set_canvas_window_min_size(window: &CanvasWindow, width: u32, height: u32);
+    pub fn set_canvas_window_resizable(window: &CanvasWindow, resizable: bool);
+    pub fn set_canvas_window_fullscreen(window: &CanvasWindow, fullscreen: bool);
+    pub fn set_canvas_window_cursor_visible(window: &CanvasWindow, visible: bool);
+    pub fn set_canvas_window_relative_mouse_mode(window: &CanvasWindow, relative: bool);
+    pub fn set_canvas_window_cursor(
+        window: &CanvasWindow,
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        hotspot_x: u32,
+        hotspot_y: u32,
+    );
+    pub fn reset_canvas_window_cursor(window: &CanvasWindow);
+    pub fn start_canvas_window_text_input(window: &CanvasWindow);
+    pub fn stop_canvas_window_text_input(window: &CanvasWindow);
+    pub fn canvas_window_width(window: &CanvasWindow) -> u32;
+    pub fn canvas_window_height(window: &CanvasWindow) -> u32;
+    pub fn canvas_window_drawable_width(window: &CanvasWindow) -> u32;
+    pub fn canvas_window_drawable_height(window: &CanvasWindow) -> u32;
+    pub fn canvas_window_scale_factor(window: &CanvasWindow) -> f64;
 
     pub fn get_window_context(window: &CanvasWindow) -> GLContext;
     pub fn gl_set_current_context(context: &GLContext);
 }
 
+// the DOM event's timeStamp, in milliseconds on the performance.now() clock
+type Time = f64;
 type MouseX = i32;
 type MouseY = i32;
 type MouseButton = i8;
 type Key = i32;
+type KeyLocation = i32;
+type KeyCode = String;
+type ModifierBits = u8;
+type Repeat = bool;
 
-type MouseMoveCallback = Box<FnMut(MouseX, MouseY) + 'static>;
-type MouseButtonCallback = Box<FnMut(MouseButton, MouseX, MouseY) + 'static>;
-type KeyboardCallback = Box<FnMut(Key) + 'static>;
+type MouseMoveCallback = Box<FnMut(Time, MouseX, MouseY) + 'static>;
+type MouseWheelCallback = Box<FnMut(Time, f32, f32) + 'static>;
+type MouseButtonCallback = Box<FnMut(Time, MouseButton, MouseX, MouseY) + 'static>;
+type KeyboardCallback = Box<FnMut(Time, Key, KeyLocation, KeyCode, ModifierBits, Repeat) + 'static>;
+type TextInputCallback = Box<FnMut(Time, String) + 'static>;
+type TextEditingCallback = Box<FnMut(Time, String, i32, i32) + 'static>;
+type TouchCallback = Box<FnMut(Time, i32, u8, MouseX, MouseY, f32) + 'static>;
+type ToggleCallback = Box<FnMut(Time, bool) + 'static>;
+type ResizeCallback = Box<FnMut(Time, u32, u32) + 'static>;
+type ScaleFactorCallback = Box<FnMut(Time, f64) + 'static>;
 
 #[wasm_bindgen]
 pub struct InputHandler {
     mouse_move: Option<MouseMoveCallback>,
+    mouse_motion: Option<MouseMoveCallback>,
     mouse_down: Option<MouseButtonCallback>,
     mouse_up: Option<MouseButtonCallback>,
+    mouse_wheel: Option<MouseWheelCallback>,
+    touch: Option<TouchCallback>,
     key_down: Option<KeyboardCallback>,
     key_up: Option<KeyboardCallback>,
+    text_input: Option<TextInputCallback>,
+    text_editing: Option<TextEditingCallback>,
+    focus: Option<ToggleCallback>,
+    visibility: Option<ToggleCallback>,
+    resize: Option<ResizeCallback>,
+    scale_factor_changed: Option<ScaleFactorCallback>,
 }
 
 #[wasm_bindgen]
 impl InputHandler {
-    pub fn mouse_move(&mut self, x: MouseX, y: MouseY) {
+    pub fn mouse_move(&mut self, time: Time, x: MouseX, y: MouseY) {
         if let Some(ref mut mouse_move) = self.mouse_move {
-            (*mouse_move)(x, y);
+            (*mouse_move)(time, x, y);
+        }
+    }
+    pub fn mouse_motion(&mut self, time: Time, x: MouseX, y: MouseY) {
+        if let Some(ref mut mouse_motion) = self.mouse_motion {
+            (*mouse_motion)(time, x, y);
         }
     }
-    pub fn mouse_down(&mut self, button: MouseButton, x: MouseX, y: MouseY) {
+    pub fn mouse_down(&mut self, time: Time, button: MouseButton, x: MouseX, y: MouseY) {
         if let Some(ref mut mouse_down) = self.mouse_down {
-            (*mouse_down)(button, x, y);
+            (*mouse_down)(time, button, x, y);
         }
     }
-    pub fn mouse_up(&mut self, button: MouseButton, x: MouseX, y: MouseY) {
+    pub fn mouse_up(&mut self, time: Time, button: MouseButton, x: MouseX, y: MouseY) {
         if let Some(ref mut mouse_up) = self.mouse_up {
-            (*mouse_up)(button, x, y);
+            (*mouse_up)(time, button, x, y);
+        }
+    }
+    pub fn mouse_wheel(&mut self, time: Time, x: f32, y: f32) {
+        if let Some(ref mut mouse_wheel) = self.mouse_wheel {
+            (*mouse_wheel)(time, x, y);
+        }
+    }
+    pub fn touch(
+        &mut self,
+        time: Time,
+        pointer_id: i32,
+        phase: u8,
+        x: MouseX,
+        y: MouseY,
+        pressure: f32,
+    ) {
+        if let Some(ref mut touch) = self.touch {
+            (*touch)(time, pointer_id, phase, x, y, pressure);
         }
     }
-    pub fn key_down(&mut self, key: Key) {
+    pub fn key_down(
+        &mut self,
+        time: Time,
+        key: Key,
+        location: KeyLocation,
+        code: KeyCode,
+        modifiers: ModifierBits,
+        repeat: Repeat,
+    ) {
         if let Some(ref mut key_down) = self.key_down {
-            (*key_down)(key);
+            (*key_down)(time, key, location, code, modifiers, repeat);
         }
     }
-    pub fn key_up(&mut self, key: Key) {
+    pub fn key_up(
+        &mut self,
+        time: Time,
+        key: Key,
+        location: KeyLocation,
+        code: KeyCode,
+        modifiers: ModifierBits,
+        repeat: Repeat,
+    ) {
         if let Some(ref mut key_up) = self.key_up {
-            (*key_up)(key);
+            (*key_up)(time, key, location, code, modifiers, repeat);
+        }
+    }
+    pub fn text_input(&mut self, time: Time, text: String) {
+        if let Some(ref mut text_input) = self.text_input {
+            (*text_input)(time, text);
+        }
+    }
+    pub fn text_editing(&mut self, time: Time, text: String, start: i32, length: i32) {
+        if let Some(ref mut text_editing) = self.text_editing {
+            (*text_editing)(time, text, start, length);
+        }
+    }
+    pub fn focus(&mut self, time: Time, focused: bool) {
+        if let Some(ref mut focus) = self.focus {
+            (*focus)(time, focused);
+        }
+    }
+    pub fn visibility(&mut self, time: Time, visible: bool) {
+        if let Some(ref mut visibility) = self.visibility {
+            (*visibility)(time, visible);
+        }
+    }
+    pub fn resize(&mut self, time: Time, width: u32, height: u32) {
+        if let Some(ref mut resize) = self.resize {
+            (*resize)(time, width, height);
+        }
+    }
+    pub fn scale_factor_changed(&mut self, time: Time, scale_factor: f64) {
+        if let Some(ref mut scale_factor_changed) = self.scale_factor_changed {
+            (*scale_factor_changed)(time, scale_factor);
         }
     }
 }
@@ -67,26 +182,70 @@ impl InputHandler {
     pub fn new() -> InputHandler {
         InputHandler {
             mouse_move: None,
+            mouse_motion: None,
             mouse_down: None,
             mouse_up: None,
+            mouse_wheel: None,
+            touch: None,
             key_down: None,
             key_up: None,
+            text_input: None,
+            text_editing: None,
+            focus: None,
+            visibility: None,
+            resize: None,
+            scale_factor_changed: None,
         }
     }
 
-    pub fn set_mouse_move<T: FnMut(MouseX, MouseY) + 'static>(&mut self, f: T) {
+    pub fn set_mouse_move<T: FnMut(Time, MouseX, MouseY) + 'static>(&mut self, f: T) {
         self.mouse_move = Some(Box::new(f));
     }
-    pub fn set_mouse_down<T: FnMut(MouseButton, MouseX, MouseY) + 'static>(&mut self, f: T) {
+    pub fn set_mouse_motion<T: FnMut(Time, MouseX, MouseY) + 'static>(&mut self, f: T) {
+        self.mouse_motion = Some(Box::new(f));
+    }
+    pub fn set_mouse_down<T: FnMut(Time, MouseButton, MouseX, MouseY) + 'static>(&mut self, f: T) {
         self.mouse_down = Some(Box::new(f));
     }
-    pub fn set_mouse_up<T: FnMut(MouseButton, MouseX, MouseY) + 'static>(&mut self, f: T) {
+    pub fn set_mouse_up<T: FnMut(Time, MouseButton, MouseX, MouseY) + 'static>(&mut self, f: T) {
         self.mouse_up = Some(Box::new(f));
     }
-    pub fn set_key_down<T: FnMut(Key) + 'static>(&mut self, f: T) {
+    pub fn set_mouse_wheel<T: FnMut(Time, f32, f32) + 'static>(&mut self, f: T) {
+        self.mouse_wheel = Some(Box::new(f));
+    }
+    pub fn set_touch<T: FnMut(Time, i32, u8, MouseX, MouseY, f32) + 'static>(&mut self, f: T) {
+        self.touch = Some(Box::new(f));
+    }
+    pub fn set_key_down<
+        T: FnMut(Time, Key, KeyLocation, KeyCode, ModifierBits, Repeat) + 'static,
+    >(
+        &mut self,
+        f: T,
+    ) {
         self.key_down = Some(Box::new(f));
     }
-    pub fn set_key_up<T: FnMut(Key) + 'static>(&mut self, f: T) {
+    pub fn set_key_up<T: FnMut(Time, Key, KeyLocation, KeyCode, ModifierBits, Repeat) + 'static>(
+        &mut self,
+        f: T,
+    ) {
         self.key_up = Some(Box::new(f));
     }
+    pub fn set_text_input<T: FnMut(Time, String) + 'static>(&mut self, f: T) {
+        self.text_input = Some(Box::new(f));
+    }
+    pub fn set_text_editing<T: FnMut(Time, String, i32, i32) + 'static>(&mut self, f: T) {
+        self.text_editing = Some(Box::new(f));
+    }
+    pub fn set_focus<T: FnMut(Time, bool) + 'static>(&mut self, f: T) {
+        self.focus = Some(Box::new(f));
+    }
+    pub fn set_visibility<T: FnMut(Time, bool) + 'static>(&mut self, f: T) {
+        self.visibility = Some(Box::new(f));
+    }
+    pub fn set_resize<T: FnMut(Time, u32, u32) + 'static>(&mut self, f: T) {
+        self.resize = Some(Box::new(f));
+    }
+    pub fn set_scale_factor_changed<T: FnMut(Time, f64) + 'static>(&mut self, f: T) {
+        self.scale_factor_changed = Some(Box::new(f));
+    }
 }
@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use failure::Error;
+
+use input::{Event, Input, InputEvent};
+use math::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlFlow {
+    Continue,
+    Exit,
+}
+
+impl From<()> for ControlFlow {
+    fn from(_: ()) -> ControlFlow {
+        ControlFlow::Continue
+    }
+}
+
+pub trait App {
+    // Called once before the first frame
+    fn start(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+    fn update(&mut self, _dt: f64, _input: &Input) -> Result<ControlFlow, Error> {
+        Ok(ControlFlow::Continue)
+    }
+    // `alpha` is how far the frame is between the previous and the current update, 1.0 unless
+    // updates run on a fixed timestep
+    fn render(&mut self, _alpha: f64) -> Result<(), Error> {
+        Ok(())
+    }
+    // `size` is in physical pixels
+    fn on_resize(&mut self, _size: Vec2<u32>) {}
+    fn on_scale_factor_changed(&mut self, _scale_factor: f64) {}
+    fn on_focus_changed(&mut self, _focused: bool) {}
+    fn on_suspend(&mut self) {}
+    fn on_resume(&mut self) {}
+    // Called once when the main loop stops, whether from an exit request or an error
+    fn on_exit(&mut self) {}
+}
+
+// Lets a plain main loop closure be used as an app. The closure needs its input parameter
+// annotated (`|dt, input: &Input|`) for its signature to be inferred.
+impl<F, R> App for F
+where
+    F: FnMut(f64, &Input) -> Result<R, Error>,
+    R: Into<ControlFlow>,
+{
+    fn update(&mut self, dt: f64, input: &Input) -> Result<ControlFlow, Error> {
+        self(dt, input).map(Into::into)
+    }
+}
+
+// Forwards lifecycle events found in a frame's input events to the app
+pub fn dispatch_events<A: App>(app: &mut A, events: &[Event]) {
+    for event in events {
+        match event.event {
+            InputEvent::Resized(width, height) => app.on_resize(Vec2::new(width, height)),
+            InputEvent::ScaleFactorChanged(scale_factor) => {
+                app.on_scale_factor_changed(scale_factor)
+            }
+            InputEvent::Focused(focused) => app.on_focus_changed(focused),
+            InputEvent::Suspended => app.on_suspend(),
+            InputEvent::Resumed => app.on_resume(),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ErrorHandler(Rc<RefCell<Box<FnMut(Error) + 'static>>>);
+
+impl ErrorHandler {
+    pub fn new<F: FnMut(Error) + 'static>(f: F) -> ErrorHandler {
+        ErrorHandler(Rc::new(RefCell::new(Box::new(f))))
+    }
+
+    pub fn set<F: FnMut(Error) + 'static>(&self, f: F) {
+        *self.0.borrow_mut() = Box::new(f);
+    }
+
+    pub fn handle(&self, error: Error) {
+        (*self.0.borrow_mut())(error)
+    }
+}
+
+impl Default for ErrorHandler {
+    fn default() -> ErrorHandler {
+        ErrorHandler::new(|e| eprintln!("error in main loop: {}", e))
+    }
+}
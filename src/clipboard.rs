@@ -0,0 +1,29 @@
+use failure::Error;
+
+use super::PlatformContext;
+use platform::clipboard as clipboard_impl;
+
+// The system clipboard, for copying and pasting text
+pub struct Clipboard(clipboard_impl::Clipboard);
+
+impl Clipboard {
+    pub fn new(context: &PlatformContext) -> Clipboard {
+        Clipboard(clipboard_impl::Clipboard::new(&context.0))
+    }
+
+    // `None` when the clipboard holds no text. Browsers only hand out the clipboard
+    // asynchronously, on the web this is the text of the last paste, copy or `request_text`.
+    pub fn text(&self) -> Result<Option<String>, Error> {
+        self.0.text()
+    }
+
+    // Starts reading the clipboard on the web, where the browser may ask the user for permission
+    // first, and `text` returns the result once the read finishes. Does nothing elsewhere.
+    pub fn request_text(&self) {
+        self.0.request_text()
+    }
+
+    pub fn set_text(&self, text: &str) -> Result<(), Error> {
+        self.0.set_text(text)
+    }
+}
@@ -0,0 +1,393 @@
+use app::App;
+use input::{Event, InputEvent, Recording};
+use platform::headless as headless_impl;
+use window::WindowId;
+
+use super::{live, playback, start, PlatformContext};
+
+pub use platform::headless::rendering::DrawCall;
+
+// Drives the main loop without a display, GPU or audio device. Every frame advances a virtual
+// clock by `frame_dt` and feeds the next batch of scripted input events to the app.
+pub struct HeadlessSettings {
+    pub frame_dt: f64,
+    pub frames: u64,
+    pub seed: u64,
+    pub input: Vec<Vec<Event>>,
+}
+
+impl Default for HeadlessSettings {
+    fn default() -> HeadlessSettings {
+        HeadlessSettings {
+            frame_dt: 1.0 / 60.0,
+            frames: 1,
+            seed: 1,
+            input: Vec::new(),
+        }
+    }
+}
+
+impl HeadlessSettings {
+    pub fn new() -> HeadlessSettings {
+        HeadlessSettings::default()
+    }
+
+    pub fn frame_dt(mut self, dt: f64) -> Self {
+        self.frame_dt = dt;
+        self
+    }
+    pub fn frames(mut self, frames: u64) -> Self {
+        self.frames = frames;
+        self
+    }
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    // Queue an input event to be delivered on the given frame
+    pub fn event(self, frame: usize, event: InputEvent) -> Self {
+        self.push_event(frame, Event::new(None, event))
+    }
+    // Headless windows are numbered in creation order, starting at `WindowId(1)`
+    pub fn window_event(self, frame: usize, window: WindowId, event: InputEvent) -> Self {
+        self.push_event(frame, Event::new(Some(window), event))
+    }
+
+    fn push_event(mut self, frame: usize, event: Event) -> Self {
+        if self.input.len() <= frame {
+            self.input.resize(frame + 1, Vec::new());
+        }
+        self.input[frame].push(event);
+        self
+    }
+}
+
+pub fn run<F: FnOnce(PlatformContext) -> T, T: App + 'static>(
+    settings: HeadlessSettings,
+    app_factory: F,
+) {
+    headless_impl::init_with(settings, |ctx| start(ctx, live(), app_factory))
+}
+
+// Replays a recording as fast as possible, with each frame's recorded dt
+pub fn run_playback<F: FnOnce(PlatformContext) -> T, T: App + 'static>(
+    recording: Recording,
+    app_factory: F,
+) {
+    // one more frame than recorded for the exit at the end of the recording
+    let settings = HeadlessSettings::new().frames(recording.frames.len() as u64 + 1);
+    headless_impl::init_with(settings, |ctx| start(ctx, playback(recording), app_factory))
+}
+
+// Draw calls issued since the last call, in submission order
+pub fn take_draw_calls() -> Vec<DrawCall> {
+    headless_impl::rendering::take_draw_calls()
+}
+
+#[test]
+fn scripted_frames() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use input::{Input, Key, Modifiers, ScanCode};
+    use math::Vec2;
+    use window::WindowSettings;
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let settings = HeadlessSettings::new()
+        .frames(4)
+        .frame_dt(0.5)
+        .event(
+            1,
+            InputEvent::KeyDown {
+                key: Key::Space,
+                scancode: ScanCode::Space,
+                modifiers: Modifiers::default(),
+                repeat: false,
+            },
+        )
+        .event(
+            2,
+            InputEvent::KeyUp {
+                key: Key::Space,
+                scancode: ScanCode::Space,
+                modifiers: Modifiers::default(),
+            },
+        );
+
+    let frames = Rc::clone(&log);
+    run(settings, move |mut context| {
+        let window = context
+            .window(WindowSettings::new().size(Vec2::new(320, 240)))
+            .unwrap();
+        move |dt, input: &Input| {
+            assert_eq!(window.renderer().screen_size(), (320, 240));
+            frames.borrow_mut().push((
+                dt,
+                input.key_is_pressed(&Key::Space),
+                input.key_is_released(&Key::Space),
+            ));
+            Ok(())
+        }
+    });
+
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            (0.5, false, false),
+            (0.5, true, false),
+            (0.5, false, true),
+            (0.5, false, false),
+        ]
+    );
+}
+
+#[test]
+fn exit_and_errors() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use app::ControlFlow;
+    use failure::Error;
+    use input::Input;
+
+    // an exit event stops the loop after the frame it was delivered on
+    let frames = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&frames);
+    let settings = HeadlessSettings::new()
+        .frames(10)
+        .event(2, InputEvent::Exit);
+    run(settings, move |_| {
+        move |_, _: &Input| {
+            counter.set(counter.get() + 1);
+            Ok(())
+        }
+    });
+    assert_eq!(frames.get(), 3);
+
+    // errors go to the error handler and end the loop
+    let errors = Rc::new(Cell::new(0));
+    let handled = Rc::clone(&errors);
+    run(HeadlessSettings::new().frames(10), move |mut context| {
+        context.set_error_handler(move |_| handled.set(handled.get() + 1));
+        move |_, _: &Input| -> Result<ControlFlow, Error> { Err(format_err!("oops")) }
+    });
+    assert_eq!(errors.get(), 1);
+}
+
+#[test]
+fn app_lifecycle() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use app::ControlFlow;
+    use failure::Error;
+    use input::Input;
+
+    struct Recorder(Rc<RefCell<Vec<String>>>);
+
+    impl App for Recorder {
+        fn start(&mut self) -> Result<(), Error> {
+            self.0.borrow_mut().push("start".into());
+            Ok(())
+        }
+        fn update(&mut self, _: f64, _: &Input) -> Result<ControlFlow, Error> {
+            self.0.borrow_mut().push("update".into());
+            Ok(ControlFlow::Continue)
+        }
+        fn on_focus_changed(&mut self, focused: bool) {
+            self.0.borrow_mut().push(format!("focus {}", focused));
+        }
+        fn on_exit(&mut self) {
+            self.0.borrow_mut().push("exit".into());
+        }
+    }
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let settings = HeadlessSettings::new()
+        .event(1, InputEvent::Focused(false))
+        .event(1, InputEvent::Exit);
+    let app_log = Rc::clone(&log);
+    run(settings, move |_| Recorder(app_log));
+
+    assert_eq!(
+        *log.borrow(),
+        vec!["start", "update", "focus false", "update", "exit"]
+    );
+
+    // running out of frames exits the app too
+    log.borrow_mut().clear();
+    let app_log = Rc::clone(&log);
+    run(HeadlessSettings::new().frames(2), move |_| {
+        Recorder(app_log)
+    });
+
+    assert_eq!(*log.borrow(), vec!["start", "update", "update", "exit"]);
+}
+
+#[test]
+fn window_resize() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use app::ControlFlow;
+    use failure::Error;
+    use input::Input;
+    use math::Vec2;
+    use window::{Window, WindowSettings};
+
+    struct Resizer(Window, Rc<RefCell<Vec<String>>>);
+
+    impl App for Resizer {
+        fn start(&mut self) -> Result<(), Error> {
+            self.0.set_size(Vec2::new(640, 480))
+        }
+        fn update(&mut self, _: f64, _: &Input) -> Result<ControlFlow, Error> {
+            let (width, height) = self.0.renderer().screen_size();
+            self.1
+                .borrow_mut()
+                .push(format!("frame {}x{}", width, height));
+            Ok(ControlFlow::Continue)
+        }
+        fn on_resize(&mut self, size: Vec2<u32>) {
+            self.1
+                .borrow_mut()
+                .push(format!("resize {}x{}", size.x, size.y));
+        }
+    }
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let app_log = Rc::clone(&log);
+    run(HeadlessSettings::new().frames(2), move |mut context| {
+        let settings = WindowSettings::new()
+            .size(Vec2::new(320, 240))
+            .resizable(true);
+        Resizer(Window::new(&mut context, settings).unwrap(), app_log)
+    });
+
+    // the resize is delivered on the frame after the size was set
+    assert_eq!(
+        *log.borrow(),
+        vec!["frame 640x480", "resize 640x480", "frame 640x480"]
+    );
+}
+
+#[test]
+fn window_close() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use input::Input;
+    use math::Vec2;
+    use window::WindowSettings;
+
+    let closed = Rc::new(RefCell::new(Vec::new()));
+    let app_closed = Rc::clone(&closed);
+    run(HeadlessSettings::new().frames(3), move |mut context| {
+        let window = context
+            .window(WindowSettings::new().size(Vec2::new(16, 16)))
+            .unwrap();
+        let id = window.id();
+        let mut window = Some(window);
+        move |_, input: &Input| {
+            let closed = input
+                .events()
+                .any(|e| e.window == Some(id) && e.event == InputEvent::Closed);
+            app_closed.borrow_mut().push(closed);
+            // dropping the window closes it
+            window.take();
+            Ok(())
+        }
+    });
+
+    assert_eq!(*closed.borrow(), vec![false, true, false]);
+}
+
+#[test]
+fn capped_frame_pacing() {
+    use input::Input;
+    use math::Vec2;
+    use window::{FramePacing, WindowSettings};
+
+    run(HeadlessSettings::new(), |mut context| {
+        let capped = |fps| {
+            WindowSettings::new()
+                .size(Vec2::new(16, 16))
+                .frame_pacing(FramePacing::Capped(fps))
+        };
+        assert!(context.window(capped(0.0)).is_err());
+        assert!(context.window(capped(-30.0)).is_err());
+        assert!(context.window(capped(30.0)).is_ok());
+        // all windows share the first window's pacing
+        assert!(context.window(capped(60.0)).is_err());
+        assert!(context
+            .window(WindowSettings::new().size(Vec2::new(16, 16)))
+            .is_ok());
+        |_, _: &Input| Ok(())
+    });
+}
+
+#[test]
+fn touch_as_mouse() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use input::{FingerId, Input, MouseButton, TouchPhase};
+    use math::Vec2;
+    use window::WindowSettings;
+
+    let window = WindowId(1);
+    let touch = |finger, phase, x| InputEvent::Touch {
+        finger: FingerId(finger),
+        phase,
+        position: (x, 10),
+        pressure: 1.0,
+    };
+    let settings = HeadlessSettings::new()
+        .window_event(0, window, touch(7, TouchPhase::Started, 10))
+        .window_event(1, window, touch(8, TouchPhase::Started, 50))
+        .window_event(1, window, touch(7, TouchPhase::Moved, 20))
+        .window_event(2, window, touch(7, TouchPhase::Ended, 30))
+        .window_event(2, window, touch(8, TouchPhase::Moved, 60));
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let frames = Rc::clone(&log);
+    run(settings, move |mut context| {
+        let window = context
+            .window(
+                WindowSettings::new()
+                    .size(Vec2::new(100, 100))
+                    .touch_as_mouse(true),
+            )
+            .unwrap();
+        move |_, input: &Input| {
+            let input = input.window(window.id()).unwrap();
+            frames.borrow_mut().push((
+                input.touches().count(),
+                input.mouse_position().x,
+                input.mouse_button_is_down(&MouseButton::Left),
+            ));
+            Ok(())
+        }
+    });
+
+    // only the first finger drives the mouse
+    assert_eq!(
+        *log.borrow(),
+        vec![(1, 10, true), (2, 20, true), (1, 30, false)]
+    );
+}
+
+#[test]
+fn clipboard() {
+    use input::Input;
+
+    run(HeadlessSettings::new(), |context| {
+        let copy = context.clipboard();
+        let paste = context.clipboard();
+        assert_eq!(paste.text().unwrap(), None);
+        copy.set_text("hello").unwrap();
+        assert_eq!(paste.text().unwrap(), Some("hello".into()));
+        |_, _: &Input| Ok(())
+    });
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use bincode;
+use failure::Error;
+
+use super::{GamepadAxis, GamepadButton, Input, Key, MouseButton, ScanCode};
+
+// A physical input an action can be bound to. Gamepad bindings match any connected gamepad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(Key),
+    ScanCode(ScanCode),
+    MouseButton(MouseButton),
+    GamepadButton(GamepadButton),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AxisBinding {
+    // -1.0 while `negative` is down and 1.0 while `positive` is down
+    Buttons {
+        negative: Binding,
+        positive: Binding,
+    },
+    GamepadAxis(GamepadAxis),
+}
+
+// Named actions and axes bound to inputs, so game code asks for "jump" instead of a key and
+// players can rebind controls. Saved and loaded with bincode.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionMap {
+    actions: HashMap<String, Vec<Binding>>,
+    axes: HashMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> ActionMap {
+        ActionMap::default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<ActionMap, Error> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    pub fn action(mut self, action: &str, binding: Binding) -> Self {
+        self.bind(action, binding);
+        self
+    }
+    pub fn axis(mut self, axis: &str, binding: AxisBinding) -> Self {
+        self.bind_axis(axis, binding);
+        self
+    }
+
+    // Adds a binding, an action can have any number of them
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let bindings = self.actions.entry(action.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let bindings = self.axes.entry(axis.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    // Replaces all bindings of an action
+    pub fn rebind(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.actions.insert(action.into(), bindings);
+    }
+
+    pub fn rebind_axis(&mut self, axis: &str, bindings: Vec<AxisBinding>) {
+        self.axes.insert(axis.into(), bindings);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], |b| &b[..])
+    }
+
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], |b| &b[..])
+    }
+
+    pub fn is_down(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| is_down(input, b))
+    }
+
+    // Pressed on the update any of its bindings went down while none were down before
+    pub fn is_pressed(&self, input: &Input, action: &str) -> bool {
+        self.is_down(input, action) && !self.was_down(input, action)
+    }
+
+    pub fn is_released(&self, input: &Input, action: &str) -> bool {
+        !self.is_down(input, action) && self.was_down(input, action)
+    }
+
+    // The strongest of the axis' bindings, from -1.0 to 1.0
+    pub fn axis_value(&self, input: &Input, axis: &str) -> f32 {
+        self.axis_bindings(axis)
+            .iter()
+            .map(|b| axis_value(input, b))
+            .fold(0.0, strongest)
+    }
+
+    fn was_down(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| was_down(input, b))
+    }
+}
+
+fn is_down(input: &Input, binding: &Binding) -> bool {
+    match *binding {
+        Binding::Key(ref key) => input.key_is_down(key),
+        Binding::ScanCode(ref scancode) => input.scancode_is_down(scancode),
+        Binding::MouseButton(ref button) => input.mouse_button_is_down(button),
+        Binding::GamepadButton(ref button) => input
+            .gamepads()
+            .filter_map(|id| input.gamepad(id))
+            .any(|gamepad| gamepad.button_is_down(button)),
+    }
+}
+
+fn was_down(input: &Input, binding: &Binding) -> bool {
+    let (pressed, released) = match *binding {
+        Binding::Key(ref key) => (input.key_is_pressed(key), input.key_is_released(key)),
+        Binding::ScanCode(ref scancode) => (
+            input.scancode_is_pressed(scancode),
+            input.scancode_is_released(scancode),
+        ),
+        Binding::MouseButton(ref button) => (
+            input.mouse_button_is_pressed(button),
+            input.mouse_button_is_released(button),
+        ),
+        Binding::GamepadButton(ref button) => {
+            return input
+                .gamepads()
+                .filter_map(|id| input.gamepad(id))
+                .any(|gamepad| {
+                    gamepad.button_is_released(button)
+                        || (gamepad.button_is_down(button) && !gamepad.button_is_pressed(button))
+                })
+        }
+    };
+    released || (is_down(input, binding) && !pressed)
+}
+
+fn axis_value(input: &Input, binding: &AxisBinding) -> f32 {
+    match *binding {
+        AxisBinding::Buttons {
+            ref negative,
+            ref positive,
+        } => {
+            let mut value = 0.0;
+            if is_down(input, negative) {
+                value -= 1.0;
+            }
+            if is_down(input, positive) {
+                value += 1.0;
+            }
+            value
+        }
+        AxisBinding::GamepadAxis(ref axis) => input
+            .gamepads()
+            .filter_map(|id| input.gamepad(id))
+            .map(|gamepad| gamepad.axis(axis))
+            .fold(0.0, strongest),
+    }
+}
+
+fn strongest(value: f32, v: f32) -> f32 {
+    if v.abs() > value.abs() {
+        v
+    } else {
+        value
+    }
+}
+
+#[test]
+fn actions() {
+    use super::{Event, InputEvent, Modifiers};
+
+    let key_down = |key, scancode| {
+        Event::new(
+            None,
+            InputEvent::KeyDown {
+                key,
+                scancode,
+                modifiers: Modifiers::default(),
+                repeat: false,
+            },
+        )
+    };
+
+    let map = ActionMap::new()
+        .action("jump", Binding::Key(Key::Space))
+        .action("jump", Binding::GamepadButton(GamepadButton::A))
+        .action("fire", Binding::MouseButton(MouseButton::Left))
+        .axis(
+            "move",
+            AxisBinding::Buttons {
+                negative: Binding::ScanCode(ScanCode::A),
+                positive: Binding::ScanCode(ScanCode::D),
+            },
+        );
+    // bindings survive a save and load
+    let map = ActionMap::from_bytes(&map.to_bytes().unwrap()).unwrap();
+
+    let mut input = Input::new();
+    input.update(vec![
+        key_down(Key::Space, ScanCode::Space),
+        key_down(Key::Q, ScanCode::A),
+    ]);
+    assert!(map.is_pressed(&input, "jump"));
+    assert!(!map.is_down(&input, "fire"));
+    assert_eq!(map.axis_value(&input, "move"), -1.0);
+
+    input.update(vec![]);
+    assert!(map.is_down(&input, "jump"));
+    assert!(!map.is_pressed(&input, "jump"));
+    assert!(!map.is_down(&input, "unbound"));
+
+    let mut map = map;
+    map.rebind("jump", vec![Binding::Key(Key::W)]);
+    assert!(!map.is_down(&input, "jump"));
+}
@@ -0,0 +1,1157 @@
+mod action_map;
+mod recording;
+
+use math::Vec2;
+use std::collections::{HashMap, HashSet};
+use window::WindowId;
+
+pub use self::action_map::{ActionMap, AxisBinding, Binding};
+pub use self::recording::{Frame, Playback, Recorder, Recording};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Key {
+    Unknown,
+    Backspace,
+    Tab,
+    Return,
+    Escape,
+    Space,
+    Exclaim,
+    Quotedbl,
+    Hash,
+    Dollar,
+    Percent,
+    Ampersand,
+    Quote,
+    LeftParen,
+    RightParen,
+    Asterisk,
+    Plus,
+    Comma,
+    Minus,
+    Period,
+    Slash,
+    Num0,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    Colon,
+    Semicolon,
+    Less,
+    Equals,
+    Greater,
+    Question,
+    At,
+    LeftBracket,
+    Backslash,
+    RightBracket,
+    Caret,
+    Underscore,
+    Backquote,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Delete,
+    CapsLock,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+    Insert,
+    Home,
+    PageUp,
+    End,
+    PageDown,
+    Right,
+    Left,
+    Down,
+    Up,
+    NumLockClear,
+    KpDivide,
+    KpMultiply,
+    KpMinus,
+    KpPlus,
+    KpEnter,
+    Kp1,
+    Kp2,
+    Kp3,
+    Kp4,
+    Kp5,
+    Kp6,
+    Kp7,
+    Kp8,
+    Kp9,
+    Kp0,
+    KpPeriod,
+    Application,
+    Power,
+    KpEquals,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    Execute,
+    Help,
+    Menu,
+    Select,
+    Stop,
+    Again,
+    Undo,
+    Cut,
+    Copy,
+    Paste,
+    Find,
+    Mute,
+    VolumeUp,
+    VolumeDown,
+    KpComma,
+    KpEqualsAS400,
+    AltErase,
+    Sysreq,
+    Cancel,
+    Clear,
+    Prior,
+    Return2,
+    Separator,
+    Out,
+    Oper,
+    ClearAgain,
+    CrSel,
+    ExSel,
+    Kp00,
+    Kp000,
+    ThousandsSeparator,
+    DecimalSeparator,
+    CurrencyUnit,
+    CurrencySubUnit,
+    KpLeftParen,
+    KpRightParen,
+    KpLeftBrace,
+    KpRightBrace,
+    KpTab,
+    KpBackspace,
+    KpA,
+    KpB,
+    KpC,
+    KpD,
+    KpE,
+    KpF,
+    KpXor,
+    KpPower,
+    KpPercent,
+    KpLess,
+    KpGreater,
+    KpAmpersand,
+    KpDblAmpersand,
+    KpVerticalBar,
+    KpDblVerticalBar,
+    KpColon,
+    KpHash,
+    KpSpace,
+    KpAt,
+    KpExclam,
+    KpMemStore,
+    KpMemRecall,
+    KpMemClear,
+    KpMemAdd,
+    KpMemSubtract,
+    KpMemMultiply,
+    KpMemDivide,
+    KpPlusMinus,
+    KpClear,
+    KpClearEntry,
+    KpBinary,
+    KpOctal,
+    KpDecimal,
+    KpHexadecimal,
+    LCtrl,
+    LShift,
+    LAlt,
+    LGui,
+    RCtrl,
+    RShift,
+    RAlt,
+    RGui,
+    Mode,
+    AudioNext,
+    AudioPrev,
+    AudioStop,
+    AudioPlay,
+    AudioMute,
+    MediaSelect,
+    Www,
+    Mail,
+    Calculator,
+    Computer,
+    AcSearch,
+    AcHome,
+    AcBack,
+    AcForward,
+    AcStop,
+    AcRefresh,
+    AcBookmarks,
+    BrightnessDown,
+    BrightnessUp,
+    DisplaySwitch,
+    KbdIllumToggle,
+    KbdIllumDown,
+    KbdIllumUp,
+    Eject,
+    Sleep,
+}
+
+// Physical key position, named after the key at that position on a US QWERTY keyboard and the
+// same whatever the keyboard layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScanCode {
+    Unknown,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    Num0,
+    Return,
+    Escape,
+    Backspace,
+    Tab,
+    Space,
+    Minus,
+    Equals,
+    LeftBracket,
+    RightBracket,
+    Backslash,
+    NonUsHash,
+    Semicolon,
+    Apostrophe,
+    Grave,
+    Comma,
+    Period,
+    Slash,
+    CapsLock,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+    Insert,
+    Home,
+    PageUp,
+    Delete,
+    End,
+    PageDown,
+    Right,
+    Left,
+    Down,
+    Up,
+    NumLockClear,
+    KpDivide,
+    KpMultiply,
+    KpMinus,
+    KpPlus,
+    KpEnter,
+    Kp1,
+    Kp2,
+    Kp3,
+    Kp4,
+    Kp5,
+    Kp6,
+    Kp7,
+    Kp8,
+    Kp9,
+    Kp0,
+    KpPeriod,
+    NonUsBackslash,
+    Application,
+    KpEquals,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    LCtrl,
+    LShift,
+    LAlt,
+    LGui,
+    RCtrl,
+    RShift,
+    RAlt,
+    RGui,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GamepadId(pub u32);
+
+// Buttons of a standard gamepad layout, named by their position on an Xbox controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+// Sticks go from -1.0 to 1.0 with positive y pointing down, triggers from 0.0 to 1.0
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    TriggerLeft,
+    TriggerRight,
+}
+
+pub const DEFAULT_GAMEPAD_DEADZONE: f32 = 0.15;
+
+// Identifies a finger for as long as it touches the screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FingerId(pub i64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    // the touch was taken over by the system, e.g. by a gesture
+    Cancelled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Touch {
+    pub finger: FingerId,
+    pub position: Vec2<i32>,
+    // 0.0 to 1.0, devices that can't sense pressure report a constant
+    pub pressure: f32,
+}
+
+// Modifier keys held down, either the left or right key counts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub gui: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MouseButton {
+    Unknown,
+    Left,
+    Middle,
+    Right,
+}
+
+// Mouse positions and window sizes are in physical pixels, the same space as the drawable and
+// `Renderer::screen_size`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    MouseMove(i32, i32),
+    // relative movement of the mouse, also reported in relative mouse mode where the cursor
+    // does not move
+    MouseMotion(i32, i32),
+    MouseDown {
+        button: MouseButton,
+        position: (i32, i32),
+    },
+    MouseUp {
+        button: MouseButton,
+        position: (i32, i32),
+    },
+    // scroll amount in wheel notches, fractional for trackpads and precise wheels, positive y
+    // scrolls up and positive x scrolls right
+    MouseWheel {
+        x: f32,
+        y: f32,
+    },
+    // `key` is the key as labeled by the keyboard layout and `scancode` its physical position,
+    // `repeat` is set for the key repeats sent while a key is held down
+    KeyDown {
+        key: Key,
+        scancode: ScanCode,
+        modifiers: Modifiers,
+        repeat: bool,
+    },
+    KeyUp {
+        key: Key,
+        scancode: ScanCode,
+        modifiers: Modifiers,
+    },
+    // `position` is in physical pixels like mouse positions
+    Touch {
+        finger: FingerId,
+        phase: TouchPhase,
+        position: (i32, i32),
+        pressure: f32,
+    },
+    // gamepad events are not tied to a window
+    GamepadConnected(GamepadId),
+    GamepadDisconnected(GamepadId),
+    GamepadButtonDown {
+        gamepad: GamepadId,
+        button: GamepadButton,
+    },
+    GamepadButtonUp {
+        gamepad: GamepadId,
+        button: GamepadButton,
+    },
+    GamepadAxisMotion {
+        gamepad: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+    // text typed while text input is active, already composed by the OS or IME
+    TextInput(String),
+    // text being composed in an IME, `start` and `length` select the part being edited and an
+    // empty `text` ends the composition
+    TextEditing {
+        text: String,
+        start: i32,
+        length: i32,
+    },
+    // text the user pasted into the page, only sent on the web where reading the clipboard from
+    // the paste shortcut would ask for permission, elsewhere read `Clipboard::text` instead
+    Paste(String),
+    Resized(u32, u32),
+    ScaleFactorChanged(f64),
+    Focused(bool),
+    Suspended,
+    Resumed,
+    Exit,
+    // the window was dropped, its input is gone after this update
+    Closed,
+}
+
+// An input event along with the window it was sent to, `None` for events that aren't tied to
+// a window like an OS quit request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub window: Option<WindowId>,
+    pub event: InputEvent,
+    // seconds since the platform started, when the event happened as close as the platform
+    // can tell
+    pub time: f64,
+}
+
+impl Event {
+    pub fn new(window: Option<WindowId>, event: InputEvent) -> Event {
+        Event {
+            window,
+            event,
+            time: 0.0,
+        }
+    }
+
+    pub fn time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+}
+
+#[derive(Clone, Default)]
+struct GamepadState {
+    pub buttons_down: HashSet<GamepadButton>,
+    pub axes: HashMap<GamepadAxis, f32>,
+}
+
+#[derive(Clone)]
+struct InputState {
+    pub keys_down: HashSet<Key>,
+    pub scancodes_down: HashSet<ScanCode>,
+    pub mouse_buttons_down: HashSet<MouseButton>,
+    pub modifiers: Modifiers,
+    pub mouse_position: Vec2<i32>,
+    // running total of relative motion, the difference between two states is the motion
+    // in between. It wraps around instead of overflowing, which leaves the differences intact.
+    pub mouse_motion: Vec2<i32>,
+    // running total like `mouse_motion`
+    pub scroll: Vec2<f64>,
+    pub gamepads: HashMap<GamepadId, GamepadState>,
+    pub touches: HashMap<FingerId, Touch>,
+}
+
+impl InputState {
+    fn new() -> InputState {
+        InputState {
+            keys_down: HashSet::new(),
+            scancodes_down: HashSet::new(),
+            mouse_buttons_down: HashSet::new(),
+            modifiers: Modifiers::default(),
+            mouse_position: Vec2::zero(),
+            mouse_motion: Vec2::zero(),
+            scroll: Vec2::zero(),
+            gamepads: HashMap::new(),
+            touches: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Input {
+    last_state: InputState,
+    current_state: InputState,
+    events: Vec<Event>,
+    exit_requested: bool,
+    windows: HashMap<WindowId, Input>,
+}
+
+impl Input {
+    pub fn new() -> Input {
+        Input {
+            last_state: InputState::new(),
+            current_state: InputState::new(),
+            events: Vec::new(),
+            exit_requested: false,
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, events: Vec<Event>) {
+        for window in events.iter().filter_map(|e| e.window) {
+            self.windows.entry(window).or_insert_with(Input::new);
+        }
+        // every window steps a frame, even when it got no events of its own
+        for (window, input) in self.windows.iter_mut() {
+            let window_events: Vec<Event> = events
+                .iter()
+                .filter(|e| e.window == Some(*window))
+                .cloned()
+                .collect();
+            input.update_state(window_events.iter().map(|e| e.event.clone()).collect());
+            input.events = window_events;
+        }
+        for e in events.iter().filter(|e| e.event == InputEvent::Closed) {
+            if let Some(window) = e.window {
+                self.windows.remove(&window);
+            }
+        }
+
+        // closing a single window is only an exit request for that window
+        let global_events = events
+            .iter()
+            .filter(|e| e.window.is_none() || !is_exit(&e.event))
+            .map(|e| e.event.clone())
+            .collect();
+        self.update_state(global_events);
+        self.events = events;
+    }
+
+    // Replaces the events of the last update, also for the window inputs
+    pub(crate) fn set_events(&mut self, events: Vec<Event>) {
+        for (window, input) in self.windows.iter_mut() {
+            input.events = events
+                .iter()
+                .filter(|e| e.window == Some(*window))
+                .cloned()
+                .collect();
+        }
+        self.events = events;
+    }
+
+    fn update_state(&mut self, events: Vec<InputEvent>) {
+        self.last_state = self.current_state.clone();
+
+        for e in events {
+            match e {
+                InputEvent::KeyDown {
+                    key,
+                    scancode,
+                    modifiers,
+                    ..
+                } => {
+                    self.current_state.keys_down.insert(key);
+                    self.current_state.scancodes_down.insert(scancode);
+                    self.current_state.modifiers = modifiers;
+                }
+                InputEvent::KeyUp {
+                    key,
+                    scancode,
+                    modifiers,
+                } => {
+                    self.current_state.keys_down.remove(&key);
+                    self.current_state.scancodes_down.remove(&scancode);
+                    self.current_state.modifiers = modifiers;
+                }
+                InputEvent::MouseDown { button, .. } => {
+                    self.current_state.mouse_buttons_down.insert(button);
+                }
+                InputEvent::MouseUp { button, .. } => {
+                    self.current_state.mouse_buttons_down.remove(&button);
+                }
+                InputEvent::MouseMove(x, y) => {
+                    self.current_state.mouse_position = Vec2::new(x, y);
+                }
+                InputEvent::MouseMotion(x, y) => {
+                    let total = self.current_state.mouse_motion;
+                    self.current_state.mouse_motion =
+                        Vec2::new(total.x.wrapping_add(x), total.y.wrapping_add(y));
+                }
+                InputEvent::MouseWheel { x, y } => {
+                    self.current_state.scroll += Vec2::new(x as f64, y as f64);
+                }
+                InputEvent::GamepadConnected(gamepad) => {
+                    self.current_state
+                        .gamepads
+                        .insert(gamepad, GamepadState::default());
+                }
+                InputEvent::GamepadDisconnected(gamepad) => {
+                    self.current_state.gamepads.remove(&gamepad);
+                }
+                InputEvent::GamepadButtonDown { gamepad, button } => {
+                    if let Some(state) = self.current_state.gamepads.get_mut(&gamepad) {
+                        state.buttons_down.insert(button);
+                    }
+                }
+                InputEvent::GamepadButtonUp { gamepad, button } => {
+                    if let Some(state) = self.current_state.gamepads.get_mut(&gamepad) {
+                        state.buttons_down.remove(&button);
+                    }
+                }
+                InputEvent::GamepadAxisMotion {
+                    gamepad,
+                    axis,
+                    value,
+                } => {
+                    if let Some(state) = self.current_state.gamepads.get_mut(&gamepad) {
+                        state.axes.insert(axis, value);
+                    }
+                }
+                InputEvent::Touch {
+                    finger,
+                    phase,
+                    position: (x, y),
+                    pressure,
+                } => match phase {
+                    TouchPhase::Started | TouchPhase::Moved => {
+                        self.current_state.touches.insert(
+                            finger,
+                            Touch {
+                                finger,
+                                position: Vec2::new(x, y),
+                                pressure,
+                            },
+                        );
+                    }
+                    TouchPhase::Ended | TouchPhase::Cancelled => {
+                        self.current_state.touches.remove(&finger);
+                    }
+                },
+                InputEvent::Exit => {
+                    self.exit_requested = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // A copy of this input whose previous state is taken from an earlier snapshot, so that
+    // pressed/released report everything that changed since then
+    pub fn with_previous(&self, previous: &Input) -> Input {
+        Input {
+            last_state: previous.current_state.clone(),
+            current_state: self.current_state.clone(),
+            events: self.events.clone(),
+            exit_requested: self.exit_requested,
+            windows: self
+                .windows
+                .iter()
+                .map(|(id, input)| match previous.windows.get(id) {
+                    Some(previous) => (*id, input.with_previous(previous)),
+                    None => (*id, input.clone()),
+                })
+                .collect(),
+        }
+    }
+
+    // Input sent to a single window, `None` until the window has received any events
+    pub fn window(&self, window: WindowId) -> Option<&Input> {
+        self.windows.get(&window)
+    }
+
+    pub fn key_is_down(&self, key: &Key) -> bool {
+        self.current_state.keys_down.contains(key)
+    }
+
+    pub fn key_is_pressed(&self, key: &Key) -> bool {
+        !self.last_state.keys_down.contains(key) && self.current_state.keys_down.contains(key)
+    }
+
+    pub fn key_is_released(&self, key: &Key) -> bool {
+        self.last_state.keys_down.contains(key) && !self.current_state.keys_down.contains(key)
+    }
+
+    // Keys by physical position, for bindings that should stay in place across layouts
+    pub fn scancode_is_down(&self, scancode: &ScanCode) -> bool {
+        self.current_state.scancodes_down.contains(scancode)
+    }
+
+    pub fn scancode_is_pressed(&self, scancode: &ScanCode) -> bool {
+        !self.last_state.scancodes_down.contains(scancode)
+            && self.current_state.scancodes_down.contains(scancode)
+    }
+
+    pub fn scancode_is_released(&self, scancode: &ScanCode) -> bool {
+        self.last_state.scancodes_down.contains(scancode)
+            && !self.current_state.scancodes_down.contains(scancode)
+    }
+
+    // Modifiers as of the last key event
+    pub fn modifiers(&self) -> Modifiers {
+        self.current_state.modifiers
+    }
+
+    pub fn mouse_button_is_down(&self, button: &MouseButton) -> bool {
+        self.current_state.mouse_buttons_down.contains(button)
+    }
+
+    pub fn mouse_button_is_pressed(&self, button: &MouseButton) -> bool {
+        !self.last_state.mouse_buttons_down.contains(button)
+            && self.current_state.mouse_buttons_down.contains(button)
+    }
+
+    pub fn mouse_button_is_released(&self, button: &MouseButton) -> bool {
+        self.last_state.mouse_buttons_down.contains(button)
+            && !self.current_state.mouse_buttons_down.contains(button)
+    }
+
+    pub fn mouse_position(&self) -> Vec2<i32> {
+        self.current_state.mouse_position
+    }
+
+    // Relative mouse movement since the previous update
+    pub fn mouse_motion(&self) -> Vec2<i32> {
+        let (current, last) = (
+            self.current_state.mouse_motion,
+            self.last_state.mouse_motion,
+        );
+        Vec2::new(
+            current.x.wrapping_sub(last.x),
+            current.y.wrapping_sub(last.y),
+        )
+    }
+
+    // Wheel scrolling since the previous update, in notches
+    pub fn scroll_delta(&self) -> Vec2<f32> {
+        let delta = self.current_state.scroll - self.last_state.scroll;
+        Vec2::new(delta.x as f32, delta.y as f32)
+    }
+
+    // Fingers currently touching the screen
+    pub fn touches<'a>(&'a self) -> impl Iterator<Item = &'a Touch> + 'a {
+        self.current_state.touches.values()
+    }
+
+    pub fn touch(&self, finger: FingerId) -> Option<&Touch> {
+        self.current_state.touches.get(&finger)
+    }
+
+    pub fn touch_is_started(&self, finger: FingerId) -> bool {
+        !self.last_state.touches.contains_key(&finger)
+            && self.current_state.touches.contains_key(&finger)
+    }
+
+    pub fn touch_is_ended(&self, finger: FingerId) -> bool {
+        self.last_state.touches.contains_key(&finger)
+            && !self.current_state.touches.contains_key(&finger)
+    }
+
+    pub fn gamepads<'a>(&'a self) -> impl Iterator<Item = GamepadId> + 'a {
+        self.current_state.gamepads.keys().cloned()
+    }
+
+    // `None` if the gamepad is not connected
+    pub fn gamepad(&self, gamepad: GamepadId) -> Option<Gamepad<'_>> {
+        let current = self.current_state.gamepads.get(&gamepad)?;
+        Some(Gamepad {
+            last: self.last_state.gamepads.get(&gamepad),
+            current,
+            deadzone: DEFAULT_GAMEPAD_DEADZONE,
+        })
+    }
+
+    // Set once the OS asked the application to quit, or for a single window's input once
+    // that window has been closed
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    // Events of the last update in the order they happened, for a window's input only the
+    // events sent to that window
+    pub fn events<'a>(&'a self) -> impl Iterator<Item = &'a Event> + 'a {
+        self.events.iter()
+    }
+}
+
+// A connected gamepad's input, queried the same way as keys
+pub struct Gamepad<'a> {
+    last: Option<&'a GamepadState>,
+    current: &'a GamepadState,
+    deadzone: f32,
+}
+
+impl<'a> Gamepad<'a> {
+    // Axis values closer to rest than `deadzone` read as 0.0, the rest of the range is
+    // rescaled to start from there. It is clamped to 0.0..=0.99 so some range is left.
+    pub fn deadzone(mut self, deadzone: f32) -> Self {
+        self.deadzone = if deadzone.is_nan() {
+            0.0
+        } else {
+            deadzone.clamp(0.0, 0.99)
+        };
+        self
+    }
+
+    pub fn button_is_down(&self, button: &GamepadButton) -> bool {
+        self.current.buttons_down.contains(button)
+    }
+
+    pub fn button_is_pressed(&self, button: &GamepadButton) -> bool {
+        !self.was_down(button) && self.current.buttons_down.contains(button)
+    }
+
+    pub fn button_is_released(&self, button: &GamepadButton) -> bool {
+        self.was_down(button) && !self.current.buttons_down.contains(button)
+    }
+
+    pub fn axis(&self, axis: &GamepadAxis) -> f32 {
+        let value = self.current.axes.get(axis).cloned().unwrap_or(0.0);
+        if value.abs() < self.deadzone {
+            0.0
+        } else {
+            value.signum() * (value.abs() - self.deadzone) / (1.0 - self.deadzone)
+        }
+    }
+
+    fn was_down(&self, button: &GamepadButton) -> bool {
+        self.last
+            .is_some_and(|last| last.buttons_down.contains(button))
+    }
+}
+
+fn is_exit(event: &InputEvent) -> bool {
+    matches!(*event, InputEvent::Exit)
+}
+
+#[test]
+fn window_routing() {
+    let game = WindowId(1);
+    let palette = WindowId(2);
+
+    let mut input = Input::new();
+    input.update(vec![
+        Event::new(
+            Some(game),
+            InputEvent::KeyDown {
+                key: Key::W,
+                scancode: ScanCode::W,
+                modifiers: Modifiers::default(),
+                repeat: false,
+            },
+        ),
+        Event::new(Some(palette), InputEvent::MouseMove(4, 8)),
+        Event::new(Some(palette), InputEvent::Exit),
+    ]);
+    assert!(input.key_is_pressed(&Key::W));
+    assert!(input.window(game).unwrap().key_is_pressed(&Key::W));
+    assert!(!input.window(palette).unwrap().key_is_down(&Key::W));
+    assert_eq!(
+        input.window(palette).unwrap().mouse_position(),
+        Vec2::new(4, 8)
+    );
+    assert!(input.window(palette).unwrap().exit_requested());
+    assert!(!input.exit_requested());
+
+    // windows without events this frame still advance
+    input.update(vec![Event::new(
+        Some(palette),
+        InputEvent::KeyDown {
+            key: Key::E,
+            scancode: ScanCode::E,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        },
+    )]);
+    assert!(input.window(game).unwrap().key_is_down(&Key::W));
+    assert!(!input.window(game).unwrap().key_is_pressed(&Key::W));
+
+    input.update(vec![Event::new(None, InputEvent::Exit).time(2.5)]);
+    assert!(input.exit_requested());
+
+    // events only last until the next update
+    assert_eq!(
+        input.events().map(|e| e.time).collect::<Vec<_>>(),
+        vec![2.5]
+    );
+    assert_eq!(input.window(palette).unwrap().events().count(), 0);
+    input.update(vec![]);
+    assert_eq!(input.events().count(), 0);
+
+    input.update(vec![Event::new(Some(palette), InputEvent::Closed)]);
+    assert!(input.window(palette).is_none());
+    assert!(input.window(game).is_some());
+}
+
+#[test]
+fn mouse_motion() {
+    let mut input = Input::new();
+    input.update(vec![
+        Event::new(None, InputEvent::MouseMotion(3, -1)),
+        Event::new(None, InputEvent::MouseMotion(2, 4)),
+    ]);
+    assert_eq!(input.mouse_motion(), Vec2::new(5, 3));
+    let snapshot = input.clone();
+
+    input.update(vec![]);
+    assert_eq!(input.mouse_motion(), Vec2::zero());
+
+    // motion accumulates across frames relative to an earlier snapshot
+    input.update(vec![Event::new(None, InputEvent::MouseMotion(1, 1))]);
+    input.update(vec![Event::new(None, InputEvent::MouseMotion(1, 1))]);
+    assert_eq!(
+        input.with_previous(&snapshot).mouse_motion(),
+        Vec2::new(2, 2)
+    );
+
+    // the running total wraps around without losing any motion
+    for _ in 0..3 {
+        input.update(vec![Event::new(
+            None,
+            InputEvent::MouseMotion(i32::MAX, i32::MIN),
+        )]);
+        assert_eq!(input.mouse_motion(), Vec2::new(i32::MAX, i32::MIN));
+    }
+
+    input.update(vec![
+        Event::new(None, InputEvent::MouseWheel { x: 0.0, y: 1.0 }),
+        Event::new(None, InputEvent::MouseWheel { x: 0.5, y: -0.25 }),
+    ]);
+    assert_eq!(input.scroll_delta(), Vec2::new(0.5, 0.75));
+    input.update(vec![]);
+    assert_eq!(input.scroll_delta(), Vec2::zero());
+}
+
+#[test]
+fn modifiers() {
+    let ctrl = Modifiers {
+        ctrl: true,
+        ..Modifiers::default()
+    };
+    let mut input = Input::new();
+    input.update(vec![
+        Event::new(
+            None,
+            InputEvent::KeyDown {
+                key: Key::RCtrl,
+                scancode: ScanCode::RCtrl,
+                modifiers: ctrl,
+                repeat: false,
+            },
+        ),
+        Event::new(
+            None,
+            InputEvent::KeyDown {
+                key: Key::Z,
+                scancode: ScanCode::Z,
+                modifiers: ctrl,
+                repeat: false,
+            },
+        ),
+    ]);
+    assert!(input.key_is_pressed(&Key::RCtrl));
+    assert!(!input.key_is_down(&Key::LCtrl));
+    assert!(input.scancode_is_pressed(&ScanCode::Z));
+    assert_eq!(input.modifiers(), ctrl);
+
+    // a repeat keeps the key down without pressing it again
+    input.update(vec![Event::new(
+        None,
+        InputEvent::KeyDown {
+            key: Key::Z,
+            scancode: ScanCode::Z,
+            modifiers: ctrl,
+            repeat: true,
+        },
+    )]);
+    assert!(input.key_is_down(&Key::Z));
+    assert!(!input.key_is_pressed(&Key::Z));
+
+    input.update(vec![Event::new(
+        None,
+        InputEvent::KeyUp {
+            key: Key::RCtrl,
+            scancode: ScanCode::RCtrl,
+            modifiers: Modifiers::default(),
+        },
+    )]);
+    assert_eq!(input.modifiers(), Modifiers::default());
+}
+
+#[test]
+fn gamepads() {
+    let pad = GamepadId(3);
+    let mut input = Input::new();
+    input.update(vec![
+        Event::new(None, InputEvent::GamepadConnected(pad)),
+        Event::new(
+            None,
+            InputEvent::GamepadButtonDown {
+                gamepad: pad,
+                button: GamepadButton::A,
+            },
+        ),
+        Event::new(
+            None,
+            InputEvent::GamepadAxisMotion {
+                gamepad: pad,
+                axis: GamepadAxis::LeftX,
+                value: -0.6,
+            },
+        ),
+        Event::new(
+            None,
+            InputEvent::GamepadAxisMotion {
+                gamepad: pad,
+                axis: GamepadAxis::LeftY,
+                value: 0.1,
+            },
+        ),
+    ]);
+    assert_eq!(input.gamepads().collect::<Vec<_>>(), vec![pad]);
+    assert!(input
+        .gamepad(pad)
+        .unwrap()
+        .button_is_pressed(&GamepadButton::A));
+    assert_eq!(input.gamepad(pad).unwrap().axis(&GamepadAxis::LeftY), 0.0);
+    let x = input
+        .gamepad(pad)
+        .unwrap()
+        .deadzone(0.2)
+        .axis(&GamepadAxis::LeftX);
+    assert!((x + 0.5).abs() < 1e-6);
+    let gamepad = input.gamepad(pad).unwrap();
+    assert!(gamepad
+        .deadzone(f32::NAN)
+        .axis(&GamepadAxis::LeftX)
+        .is_finite());
+
+    input.update(vec![Event::new(
+        None,
+        InputEvent::GamepadButtonUp {
+            gamepad: pad,
+            button: GamepadButton::A,
+        },
+    )]);
+    assert!(input
+        .gamepad(pad)
+        .unwrap()
+        .button_is_released(&GamepadButton::A));
+
+    input.update(vec![Event::new(None, InputEvent::GamepadDisconnected(pad))]);
+    assert!(input.gamepad(pad).is_none());
+}
@@ -0,0 +1,179 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Cursor, Write};
+use std::path::Path;
+
+use bincode;
+use failure::Error;
+
+use super::{Event, InputEvent};
+
+// The input of one frame, everything that isn't deterministic about it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    pub dt: f64,
+    pub events: Vec<Event>,
+}
+
+// Writes frames to a file as they happen, so a recording survives the app crashing
+pub struct Recorder {
+    writer: Box<Write>,
+}
+
+impl Recorder {
+    pub fn new<W: Write + 'static>(writer: W) -> Recorder {
+        Recorder {
+            writer: Box::new(writer),
+        }
+    }
+
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Recorder, Error> {
+        Ok(Recorder::new(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn record(&mut self, dt: f64, events: &[Event]) -> Result<(), Error> {
+        let frame = Frame {
+            dt,
+            events: events.to_vec(),
+        };
+        bincode::serialize_into(&mut self.writer, &frame)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Recording {
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    // A frame cut off by the app crashing mid-write ends the recording, the frames before it are
+    // still loaded
+    pub fn from_bytes(bytes: &[u8]) -> Result<Recording, Error> {
+        let mut reader = Cursor::new(bytes);
+        let mut frames = Vec::new();
+        while (reader.position() as usize) < bytes.len() {
+            match bincode::deserialize_from(&mut reader) {
+                Ok(frame) => frames.push(frame),
+                Err(ref e) if is_truncated(e) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(Recording { frames })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Recording, Error> {
+        Recording::from_bytes(&fs::read(path)?)
+    }
+}
+
+fn is_truncated(error: &bincode::ErrorKind) -> bool {
+    match *error {
+        bincode::ErrorKind::Io(ref e) => e.kind() == io::ErrorKind::UnexpectedEof,
+        _ => false,
+    }
+}
+
+// Replays a recording in place of live input. Exit requests still come through so a replay can
+// be closed early, and once the recording runs out the app is asked to exit.
+pub struct Playback {
+    frames: ::std::vec::IntoIter<Frame>,
+}
+
+impl Playback {
+    pub fn new(recording: Recording) -> Playback {
+        Playback {
+            frames: recording.frames.into_iter(),
+        }
+    }
+
+    pub fn next_frame(&mut self, dt: f64, live_events: Vec<Event>) -> Frame {
+        let exits = live_events
+            .into_iter()
+            .filter(|e| e.event == InputEvent::Exit);
+        match self.frames.next() {
+            Some(mut frame) => {
+                frame.events.extend(exits);
+                frame
+            }
+            None => Frame {
+                dt,
+                events: vec![Event::new(None, InputEvent::Exit)],
+            },
+        }
+    }
+}
+
+#[test]
+fn recording() {
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+
+    use super::{Key, Modifiers, ScanCode};
+
+    #[derive(Clone)]
+    struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let buffer = Buffer(Rc::new(RefCell::new(Vec::new())));
+    let mut recorder = Recorder::new(buffer.clone());
+    let frames = vec![
+        Frame {
+            dt: 0.25,
+            events: vec![Event::new(None, InputEvent::MouseMove(3, 4))],
+        },
+        Frame {
+            dt: 0.5,
+            events: vec![],
+        },
+        Frame {
+            dt: 0.125,
+            events: vec![Event::new(
+                None,
+                InputEvent::KeyUp {
+                    key: Key::Space,
+                    scancode: ScanCode::Space,
+                    modifiers: Modifiers::default(),
+                },
+            )],
+        },
+    ];
+    for frame in &frames {
+        recorder.record(frame.dt, &frame.events).unwrap();
+    }
+    let recording = Recording::from_bytes(&buffer.0.borrow()).unwrap();
+    assert_eq!(recording.frames, frames);
+
+    // a partly written last frame is dropped
+    let bytes = buffer.0.borrow();
+    let truncated = Recording::from_bytes(&bytes[..bytes.len() - 1]).unwrap();
+    assert_eq!(truncated.frames, &frames[..2]);
+
+    // live events other than exits are ignored, and the end of the recording is an exit
+    let mut playback = Playback::new(recording);
+    let live = vec![
+        Event::new(None, InputEvent::MouseMove(9, 9)),
+        Event::new(None, InputEvent::Exit),
+    ];
+    let mut first = frames[0].clone();
+    first.events.push(Event::new(None, InputEvent::Exit));
+    assert_eq!(playback.next_frame(1.0, live), first);
+    assert_eq!(playback.next_frame(1.0, vec![]), frames[1]);
+    assert_eq!(playback.next_frame(1.0, vec![]), frames[2]);
+    assert_eq!(
+        playback.next_frame(1.0, vec![]),
+        Frame {
+            dt: 1.0,
+            events: vec![Event::new(None, InputEvent::Exit)],
+        }
+    );
+}
@@ -9,37 +9,45 @@ extern crate failure;
 extern crate num_traits;
 
 // Web backend
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "headless")))]
 extern crate js;
 
 // Native backend
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "headless")))]
 extern crate gl;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "headless")))]
 extern crate rand;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "headless")))]
 extern crate sdl2;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "headless")))]
 extern crate ws;
 
 mod platform;
 
+pub mod app;
 pub mod assets;
 pub mod audio;
+pub mod clipboard;
 pub mod graphics;
+#[cfg(feature = "headless")]
+pub mod headless;
 pub mod input;
 pub mod math;
 pub mod rendering;
+pub mod timestep;
 pub mod util;
 pub mod window;
 
 use failure::Error;
-use input::Input;
+use input::{Event, Input, Playback, Recorder, Recording};
 
+use app::{App, ControlFlow, ErrorHandler};
 use audio::AudioDevice;
+use clipboard::Clipboard;
+use timestep::{FixedStep, FixedTimestep};
 use window::{Window, WindowSettings};
 
-pub struct PlatformContext(platform::Context);
+pub struct PlatformContext(platform::Context, ErrorHandler);
 
 impl PlatformContext {
     pub fn window(&mut self, settings: WindowSettings) -> Result<Window, Error> {
@@ -52,17 +60,146 @@ impl PlatformContext {
     ) -> AudioDevice {
         AudioDevice::new(self, channels, cb)
     }
+    pub fn clipboard(&self) -> Clipboard {
+        Clipboard::new(self)
+    }
+    // Called with any error returned from the main loop, right before the application shuts down
+    pub fn set_error_handler<F: FnMut(Error) + 'static>(&mut self, handler: F) {
+        self.1.set(handler)
+    }
 }
 
 pub fn rand() -> f32 {
     platform::rand::rand()
 }
 
-pub fn init<
-    F: FnOnce(PlatformContext) -> T,
-    T: FnMut(f64, &Input) -> Result<(), Error> + 'static,
->(
+pub fn init<F: FnOnce(PlatformContext) -> T, T: App + 'static>(app_factory: F) {
+    platform::init(|ctx| start(ctx, live(), app_factory))
+}
+
+// Runs the app as usual while writing every frame's input events and dt to the recorder
+pub fn init_recording<F: FnOnce(PlatformContext) -> T, T: App + 'static>(
+    recorder: Recorder,
+    app_factory: F,
+) {
+    platform::init(|ctx| start(ctx, record(recorder), app_factory))
+}
+
+// Feeds the app a recording instead of live input, exiting when the recording runs out
+pub fn init_playback<F: FnOnce(PlatformContext) -> T, T: App + 'static>(
+    recording: Recording,
+    app_factory: F,
+) {
+    platform::init(|ctx| start(ctx, playback(recording), app_factory))
+}
+
+// Runs the app's update at a fixed tick rate, independent of the frame rate, and renders once
+// per frame with the interpolation factor between the last two ticks
+pub fn init_fixed<F: FnOnce(PlatformContext) -> T, T: App + 'static>(
+    timestep: FixedTimestep,
     app_factory: F,
 ) {
-    platform::init(|ctx| app_factory(PlatformContext(ctx)))
+    init(|ctx| FixedStep::new(timestep, app_factory(ctx)))
+}
+
+// Where a frame's dt and input events come from, given the live ones from the platform
+type InputSource = Box<FnMut(f64, Vec<Event>) -> Result<(f64, Vec<Event>), Error>>;
+
+fn live() -> InputSource {
+    Box::new(|dt, events| Ok((dt, events)))
+}
+
+fn record(mut recorder: Recorder) -> InputSource {
+    Box::new(move |dt, events| {
+        recorder.record(dt, &events)?;
+        Ok((dt, events))
+    })
+}
+
+fn playback(recording: Recording) -> InputSource {
+    let mut playback = Playback::new(recording);
+    Box::new(move |dt, events| {
+        let frame = playback.next_frame(dt, events);
+        Ok((frame.dt, frame.events))
+    })
+}
+
+// An app between its start and its exit
+struct Running<T: App> {
+    app: T,
+    started: bool,
+    exited: bool,
+}
+
+impl<T: App> Running<T> {
+    fn exit(&mut self) {
+        if !self.exited {
+            self.exited = true;
+            self.app.on_exit();
+        }
+    }
+}
+
+// The platform may stop running frames without the app asking to exit, e.g. when a headless
+// run is out of frames
+impl<T: App> Drop for Running<T> {
+    fn drop(&mut self) {
+        if self.started {
+            self.exit();
+        }
+    }
+}
+
+fn start<F: FnOnce(PlatformContext) -> T, T: App + 'static>(
+    ctx: platform::Context,
+    mut source: InputSource,
+    app_factory: F,
+) -> impl FnMut(f64, Vec<Event>) -> ControlFlow + 'static {
+    let error_handler = ErrorHandler::default();
+    let mut running = Running {
+        app: app_factory(PlatformContext(ctx, error_handler.clone())),
+        started: false,
+        exited: false,
+    };
+    let mut input = Input::new();
+    move |dt, events| {
+        let flow = if running.started {
+            Ok(())
+        } else {
+            running.started = true;
+            running.app.start()
+        };
+        let app = &mut running.app;
+        let flow = flow
+            .and_then(|_| source(dt, events))
+            .and_then(|(dt, events)| frame(app, &mut input, dt, events));
+        let flow = flow.unwrap_or_else(|e| {
+            error_handler.handle(e);
+            ControlFlow::Exit
+        });
+        if flow == ControlFlow::Exit {
+            running.exit();
+        }
+        flow
+    }
+}
+
+fn frame<T: App>(
+    app: &mut T,
+    input: &mut Input,
+    dt: f64,
+    events: Vec<Event>,
+) -> Result<ControlFlow, Error> {
+    app::dispatch_events(app, &events);
+    input.update(events);
+
+    let flow = app.update(dt, input)?;
+    app.render(1.0)?;
+
+    // the app gets to see the exit event for one frame before shutting down
+    if input.exit_requested() {
+        Ok(ControlFlow::Exit)
+    } else {
+        Ok(flow)
+    }
 }
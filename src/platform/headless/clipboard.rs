@@ -0,0 +1,26 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use failure::Error;
+
+use super::Context;
+
+// Text is kept in memory, shared by every clipboard of the same context
+pub struct Clipboard(Rc<RefCell<Option<String>>>);
+
+impl Clipboard {
+    pub fn new(context: &Context) -> Clipboard {
+        Clipboard(Rc::clone(&context.clipboard))
+    }
+
+    pub fn text(&self) -> Result<Option<String>, Error> {
+        Ok(self.0.borrow().clone())
+    }
+
+    pub fn request_text(&self) {}
+
+    pub fn set_text(&self, text: &str) -> Result<(), Error> {
+        *self.0.borrow_mut() = Some(text.into());
+        Ok(())
+    }
+}
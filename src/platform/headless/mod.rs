@@ -0,0 +1,86 @@
+pub mod audio;
+pub mod clipboard;
+pub mod rand;
+pub mod rendering;
+pub mod window;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use failure::Error;
+
+use app::ControlFlow;
+use headless::HeadlessSettings;
+use input::Event;
+use window::{FramePacing, WindowSettings};
+
+use self::window::Window;
+
+pub struct Context {
+    windows: Rc<RefCell<Vec<Rc<Window>>>>,
+    window_count: u32,
+    frame_pacing: Option<FramePacing>,
+    clipboard: Rc<RefCell<Option<String>>>,
+}
+
+impl Context {
+    pub fn window(&mut self, settings: WindowSettings) -> Result<Rc<Window>, Error> {
+        let frame_pacing = FramePacing::shared(self.frame_pacing, settings.frame_pacing)?;
+        let window = Rc::new(Window::new(self, settings)?);
+        self.frame_pacing = Some(frame_pacing);
+        self.windows.borrow_mut().push(window.clone());
+        Ok(window)
+    }
+}
+
+pub fn init<F: FnOnce(Context) -> T, T: FnMut(f64, Vec<Event>) -> ControlFlow + 'static>(
+    app_factory: F,
+) {
+    init_with(HeadlessSettings::default(), app_factory)
+}
+
+pub fn init_with<F: FnOnce(Context) -> T, T: FnMut(f64, Vec<Event>) -> ControlFlow + 'static>(
+    settings: HeadlessSettings,
+    app_factory: F,
+) {
+    let HeadlessSettings {
+        frame_dt,
+        frames,
+        seed,
+        input: script,
+    } = settings;
+
+    rand::seed(seed);
+    rendering::take_draw_calls();
+
+    let windows = Rc::new(RefCell::new(Vec::new()));
+    let context = Context {
+        windows: Rc::clone(&windows),
+        window_count: 0,
+        frame_pacing: None,
+        clipboard: Rc::new(RefCell::new(None)),
+    };
+
+    let mut main_loop = app_factory(context);
+    let frames = frames.max(script.len() as u64);
+    let mut script = script.into_iter();
+    for frame in 0..frames {
+        // everything happens at the start of the frame on the virtual clock
+        let time = frame as f64 * frame_dt;
+        let mut events = super::close_dropped_windows(&windows, time);
+        events.extend(windows.borrow().iter().flat_map(|w| w.events()));
+        events.extend(script.next().unwrap_or_default());
+        for e in events.iter_mut() {
+            e.time = time;
+        }
+        for w in windows.borrow().iter() {
+            events = w.synthesize_mouse_events(events);
+        }
+        if main_loop(frame_dt, events) == ControlFlow::Exit {
+            break;
+        }
+    }
+
+    drop(main_loop);
+    windows.borrow_mut().clear();
+}
@@ -0,0 +1,21 @@
+use std::cell::Cell;
+
+thread_local! {
+    static STATE: Cell<u64> = const { Cell::new(1) };
+}
+
+pub fn seed(seed: u64) {
+    // xorshift gets stuck on a zero state
+    STATE.with(|s| s.set(seed.max(1)));
+}
+
+pub fn rand() -> f32 {
+    STATE.with(|s| {
+        let mut x = s.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        s.set(x);
+        (x >> 40) as f32 / (1u64 << 24) as f32
+    })
+}
@@ -0,0 +1,174 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use failure::Error;
+
+use assets::Image;
+use math::Vec2;
+use rendering::{DrawRange, Index, IndexType, Primitive, RenderState, TextureFiltering, Vertex};
+
+thread_local! {
+    static CURRENT_SIZE: Cell<Vec2<u32>> = const { Cell::new(Vec2 { x: 0, y: 0 }) };
+    static DRAW_CALLS: RefCell<Vec<DrawCall>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Clone, Debug)]
+pub struct DrawCall {
+    pub state: RenderState,
+    pub primitive: Primitive,
+    // the vertices drawn, or all of the buffer's vertices for indexed draws
+    pub vertex_count: usize,
+    // `None` for draws that aren't indexed
+    pub index_count: Option<usize>,
+    // bytes per vertex of the program's vertex format
+    pub vertex_stride: usize,
+    // names of the uniforms set on the program, sorted
+    pub uniforms: Vec<String>,
+}
+
+pub fn set_current_size(size: Vec2<u32>) {
+    CURRENT_SIZE.with(|s| s.set(size));
+}
+
+pub fn take_draw_calls() -> Vec<DrawCall> {
+    DRAW_CALLS.with(|calls| calls.borrow_mut().drain(0..).collect())
+}
+
+pub struct VertexBuffer {
+    len: Cell<usize>,
+}
+
+pub struct IndexBuffer;
+
+#[derive(Clone)]
+pub struct Texture {
+    size: (u32, u32),
+}
+
+impl Texture {
+    pub fn set_region(&self, image: &Image, offset: (u32, u32)) {
+        debug_assert!(
+            offset.0 + image.width <= self.size.0 && offset.1 + image.height <= self.size.1,
+            "texture region out of bounds"
+        );
+    }
+}
+
+#[derive(Clone)]
+pub enum Uniform {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
+    Vec2((f32, f32)),
+    Vec3((f32, f32, f32)),
+    Vec4((f32, f32, f32, f32)),
+    Mat2([f32; 4]),
+    Mat3([f32; 9]),
+    Mat4([f32; 16]),
+    FloatArray(Vec<f32>),
+    IntArray(Vec<i32>),
+    Vec2Array(Vec<(f32, f32)>),
+    Vec3Array(Vec<(f32, f32, f32)>),
+    Vec4Array(Vec<(f32, f32, f32, f32)>),
+    Texture(Texture),
+}
+
+pub struct Program {
+    uniforms: HashMap<String, Uniform>,
+}
+
+impl Program {
+    // without a shader compiler there is no telling which uniforms are active, every name is
+    // accepted
+    pub fn set_uniform(&mut self, name: &str, uniform: Uniform) -> Result<(), Error> {
+        self.uniforms.insert(name.into(), uniform);
+        Ok(())
+    }
+    pub fn uniforms(&self) -> impl Iterator<Item = (&String, &Uniform)> {
+        self.uniforms.iter()
+    }
+}
+
+pub fn screen_size() -> (i32, i32) {
+    let size = CURRENT_SIZE.with(|s| s.get());
+    (size.x as i32, size.y as i32)
+}
+pub fn create_vertex_buffer() -> Result<VertexBuffer, Error> {
+    Ok(VertexBuffer { len: Cell::new(0) })
+}
+pub fn create_index_buffer() -> Result<IndexBuffer, Error> {
+    Ok(IndexBuffer)
+}
+pub fn create_program(_vs: &str, _fs: &str) -> Result<Program, Error> {
+    Ok(Program {
+        uniforms: HashMap::new(),
+    })
+}
+
+pub fn create_texture(
+    size: (u32, u32),
+    _filtering: Option<TextureFiltering>,
+) -> Result<Texture, Error> {
+    Ok(Texture { size })
+}
+
+pub fn upload_vertices<V: Vertex>(vertex_buffer: &VertexBuffer, vertices: &Vec<V>) {
+    vertex_buffer.len.set(vertices.len());
+}
+
+pub fn upload_indices<I: Index>(
+    _vertex_buffer: &VertexBuffer,
+    _index_buffer: &IndexBuffer,
+    _indices: &Vec<I>,
+) {
+}
+
+pub fn draw_vertices<V: Vertex>(
+    _vertex_buffer: &VertexBuffer,
+    program: &Program,
+    state: &RenderState,
+    range: DrawRange,
+) -> Result<(), Error> {
+    push_draw_call::<V>(program, state, range.primitive, range.count, None);
+    Ok(())
+}
+
+pub fn draw_indexed<V: Vertex>(
+    vertex_buffer: &VertexBuffer,
+    _index_buffer: &IndexBuffer,
+    program: &Program,
+    state: &RenderState,
+    _index_type: IndexType,
+    range: DrawRange,
+) -> Result<(), Error> {
+    push_draw_call::<V>(
+        program,
+        state,
+        range.primitive,
+        vertex_buffer.len.get(),
+        Some(range.count),
+    );
+    Ok(())
+}
+
+fn push_draw_call<V: Vertex>(
+    program: &Program,
+    state: &RenderState,
+    primitive: Primitive,
+    vertex_count: usize,
+    index_count: Option<usize>,
+) {
+    let mut uniforms: Vec<String> = program.uniforms().map(|(name, _)| name.clone()).collect();
+    uniforms.sort();
+    let call = DrawCall {
+        state: *state,
+        primitive,
+        vertex_count,
+        index_count,
+        vertex_stride: V::stride(),
+        uniforms,
+    };
+    DRAW_CALLS.with(|calls| calls.borrow_mut().push(call));
+}
+
+pub fn clear(_color: Option<(f32, f32, f32, f32)>) {}
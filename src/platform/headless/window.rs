@@ -0,0 +1,119 @@
+use std::cell::{Cell, RefCell};
+
+use failure::Error;
+
+use input::{Event, InputEvent};
+use math::Vec2;
+use platform::touch::TouchMouse;
+use window::{Cursor, Fullscreen, WindowId, WindowSettings};
+
+use super::rendering;
+use super::Context;
+
+pub struct Window {
+    id: WindowId,
+    size: Cell<Vec2<u32>>,
+    events: RefCell<Vec<InputEvent>>,
+    touch_mouse: Option<TouchMouse>,
+}
+
+pub struct GLContext;
+
+impl Window {
+    pub fn new(context: &mut Context, settings: WindowSettings) -> Result<Window, Error> {
+        let WindowSettings {
+            size,
+            touch_as_mouse,
+            ..
+        } = settings;
+
+        let size = size.ok_or_else(|| format_err!("missing size in WindowSettings"))?;
+        // ids are handed out in creation order starting from 1, so scripts can address windows
+        context.window_count += 1;
+        let id = WindowId(context.window_count);
+        Ok(Window {
+            id,
+            size: Cell::new(size),
+            events: RefCell::new(Vec::new()),
+            touch_mouse: if touch_as_mouse {
+                Some(TouchMouse::new(id))
+            } else {
+                None
+            },
+        })
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        let id = self.id;
+        self.events
+            .borrow_mut()
+            .drain(0..)
+            .map(|e| Event::new(Some(id), e))
+            .collect()
+    }
+
+    pub fn synthesize_mouse_events(&self, events: Vec<Event>) -> Vec<Event> {
+        match self.touch_mouse {
+            Some(ref touch_mouse) => touch_mouse.synthesize(events),
+            None => events,
+        }
+    }
+
+    pub fn size(&self) -> Vec2<u32> {
+        self.size.get()
+    }
+
+    pub fn physical_size(&self) -> Vec2<u32> {
+        self.size.get()
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        1.0
+    }
+
+    // resizes show up as a Resized event on the next frame, like they would from the OS
+    pub fn set_size(&self, size: Vec2<u32>) -> Result<(), Error> {
+        if size != self.size.get() {
+            self.size.set(size);
+            self.events
+                .borrow_mut()
+                .push(InputEvent::Resized(size.x, size.y));
+        }
+        Ok(())
+    }
+
+    pub fn set_cursor_visible(&self, _: bool) {}
+
+    pub fn set_cursor_grab(&self, _: bool) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn set_relative_mouse_mode(&self, _: bool) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn set_cursor(&self, _: Option<&Cursor>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn start_text_input(&self) {}
+
+    pub fn stop_text_input(&self) {}
+
+    // there is no display to cover, the size stays what it was
+    pub fn set_fullscreen(&self, _: Option<Fullscreen>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn gl_create_context(&self) -> GLContext {
+        GLContext
+    }
+
+    pub fn gl_set_current(&self, _: &GLContext) {
+        rendering::set_current_size(self.size.get());
+    }
+}
@@ -1,11 +1,44 @@
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "headless")))]
 mod native;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "headless")))]
 use self::native as platform_impl;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "headless")))]
 mod web;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "headless")))]
 use self::web as platform_impl;
 
-pub use self::platform_impl::{audio, init, rand, rendering, window, Context};
+#[cfg(feature = "headless")]
+pub mod headless;
+#[cfg(feature = "headless")]
+use self::headless as platform_impl;
+
+mod touch;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use input::{Event, InputEvent};
+
+pub use self::platform_impl::{audio, clipboard, init, rand, rendering, window, Context};
+
+// Windows only the context still holds on to were dropped by the app. They are closed here and
+// reported with `Closed` events.
+fn close_dropped_windows(
+    windows: &RefCell<Vec<Rc<self::window::Window>>>,
+    time: f64,
+) -> Vec<Event> {
+    let mut windows = windows.borrow_mut();
+    let closed = windows
+        .iter()
+        .filter(|w| Rc::strong_count(w) == 1)
+        .map(|w| Event::new(Some(w.id()), InputEvent::Closed).time(time))
+        .collect();
+    windows.retain(|w| Rc::strong_count(w) > 1);
+    closed
+}
+
+// Frame deltas handed to the app are capped, so a stall (a throttled browser tab, a debugger
+// break, a dragged window) doesn't turn into one huge simulation step
+#[cfg(not(feature = "headless"))]
+const MAX_FRAME_DT: f64 = 0.25;
@@ -0,0 +1,30 @@
+use failure::Error;
+use sdl2::clipboard::ClipboardUtil;
+
+use super::Context;
+
+pub struct Clipboard(ClipboardUtil);
+
+impl Clipboard {
+    pub fn new(context: &Context) -> Clipboard {
+        Clipboard(context.video.clipboard())
+    }
+
+    pub fn text(&self) -> Result<Option<String>, Error> {
+        if !self.0.has_clipboard_text() {
+            return Ok(None);
+        }
+        self.0
+            .clipboard_text()
+            .map(Some)
+            .map_err(|e| format_err!("{}", e))
+    }
+
+    pub fn request_text(&self) {}
+
+    pub fn set_text(&self, text: &str) -> Result<(), Error> {
+        self.0
+            .set_clipboard_text(text)
+            .map_err(|e| format_err!("{}", e))
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use sdl2;
+use sdl2::controller::{Axis as Sdl2Axis, Button as Sdl2Button, GameController};
+use sdl2::event::Event as Sdl2Event;
+
+use input::{Event, GamepadAxis, GamepadButton, GamepadId, InputEvent};
+
+use super::input::to_events;
+
+// Keeps connected controllers open, SDL only sends their events while they are
+pub struct Gamepads {
+    subsystem: sdl2::GameControllerSubsystem,
+    controllers: HashMap<u32, GameController>,
+}
+
+impl Gamepads {
+    pub fn new(subsystem: sdl2::GameControllerSubsystem) -> Gamepads {
+        Gamepads {
+            subsystem,
+            controllers: HashMap::new(),
+        }
+    }
+
+    // Converts controller events and passes everything else on to `input::to_events`.
+    // Gamepads are identified by their joystick instance id, which stays the same until they
+    // are disconnected. A controller SDL fails to open is skipped, the app never sees it connect.
+    pub fn to_events(&mut self, event: Sdl2Event) -> Vec<Event> {
+        let event = match event {
+            Sdl2Event::ControllerDeviceAdded { which, .. } => match self.subsystem.open(which) {
+                Ok(controller) => {
+                    let id = controller.instance_id();
+                    self.controllers.insert(id, controller);
+                    Some(InputEvent::GamepadConnected(GamepadId(id)))
+                }
+                Err(_) => None,
+            },
+            Sdl2Event::ControllerDeviceRemoved { which, .. } => self
+                .controllers
+                .remove(&which)
+                .map(|_| InputEvent::GamepadDisconnected(GamepadId(which))),
+            Sdl2Event::ControllerButtonDown { which, button, .. } => {
+                to_button(button).map(|button| InputEvent::GamepadButtonDown {
+                    gamepad: GamepadId(which),
+                    button,
+                })
+            }
+            Sdl2Event::ControllerButtonUp { which, button, .. } => {
+                to_button(button).map(|button| InputEvent::GamepadButtonUp {
+                    gamepad: GamepadId(which),
+                    button,
+                })
+            }
+            Sdl2Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => Some(InputEvent::GamepadAxisMotion {
+                gamepad: GamepadId(which),
+                axis: to_axis(axis),
+                value: (value as f32 / 32767.0).max(-1.0),
+            }),
+            event => return to_events(event),
+        };
+        event.into_iter().map(|e| Event::new(None, e)).collect()
+    }
+}
+
+fn to_button(button: Sdl2Button) -> Option<GamepadButton> {
+    Some(match button {
+        Sdl2Button::A => GamepadButton::A,
+        Sdl2Button::B => GamepadButton::B,
+        Sdl2Button::X => GamepadButton::X,
+        Sdl2Button::Y => GamepadButton::Y,
+        Sdl2Button::Back => GamepadButton::Back,
+        Sdl2Button::Guide => GamepadButton::Guide,
+        Sdl2Button::Start => GamepadButton::Start,
+        Sdl2Button::LeftStick => GamepadButton::LeftStick,
+        Sdl2Button::RightStick => GamepadButton::RightStick,
+        Sdl2Button::LeftShoulder => GamepadButton::LeftShoulder,
+        Sdl2Button::RightShoulder => GamepadButton::RightShoulder,
+        Sdl2Button::DPadUp => GamepadButton::DPadUp,
+        Sdl2Button::DPadDown => GamepadButton::DPadDown,
+        Sdl2Button::DPadLeft => GamepadButton::DPadLeft,
+        Sdl2Button::DPadRight => GamepadButton::DPadRight,
+        _ => return None,
+    })
+}
+
+fn to_axis(axis: Sdl2Axis) -> GamepadAxis {
+    match axis {
+        Sdl2Axis::LeftX => GamepadAxis::LeftX,
+        Sdl2Axis::LeftY => GamepadAxis::LeftY,
+        Sdl2Axis::RightX => GamepadAxis::RightX,
+        Sdl2Axis::RightY => GamepadAxis::RightY,
+        Sdl2Axis::TriggerLeft => GamepadAxis::TriggerLeft,
+        Sdl2Axis::TriggerRight => GamepadAxis::TriggerRight,
+    }
+}
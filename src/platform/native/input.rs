@@ -1,6 +1,221 @@
-use input::{Key, MouseButton};
-use sdl2::keyboard::Keycode as Sdl2Keycode;
-use sdl2::mouse::MouseButton as Sdl2MouseButton;
+use input::{Event, FingerId, InputEvent, Key, Modifiers, MouseButton, ScanCode, TouchPhase};
+use sdl2::event::{Event as Sdl2Event, WindowEvent as Sdl2WindowEvent};
+use sdl2::keyboard::{Keycode as Sdl2Keycode, Mod as Sdl2Mod, Scancode as Sdl2Scancode};
+use sdl2::mouse::{MouseButton as Sdl2MouseButton, MouseWheelDirection};
+use window::WindowId;
+
+use super::window::Window;
+
+pub fn to_events(event: Sdl2Event) -> Vec<Event> {
+    let mut events = Vec::new();
+    if let Sdl2Event::MouseMotion {
+        window_id,
+        xrel,
+        yrel,
+        ..
+    } = event
+    {
+        events.push(Event::new(
+            Some(WindowId(window_id)),
+            InputEvent::MouseMotion(xrel, yrel),
+        ));
+    }
+    events.extend(to_event(event));
+    events
+}
+
+fn to_event(event: Sdl2Event) -> Option<Event> {
+    let (window_id, event) = match event {
+        Sdl2Event::Quit { .. } => (None, InputEvent::Exit),
+        Sdl2Event::AppWillEnterBackground { .. } => (None, InputEvent::Suspended),
+        Sdl2Event::AppDidEnterForeground { .. } => (None, InputEvent::Resumed),
+        Sdl2Event::Window {
+            window_id,
+            win_event,
+            ..
+        } => {
+            let event = match win_event {
+                Sdl2WindowEvent::Close => InputEvent::Exit,
+                Sdl2WindowEvent::SizeChanged(width, height) => {
+                    InputEvent::Resized(width as u32, height as u32)
+                }
+                Sdl2WindowEvent::FocusGained => InputEvent::Focused(true),
+                Sdl2WindowEvent::FocusLost => InputEvent::Focused(false),
+                Sdl2WindowEvent::Minimized => InputEvent::Suspended,
+                Sdl2WindowEvent::Restored => InputEvent::Resumed,
+                _ => return None,
+            };
+            (Some(window_id), event)
+        }
+        Sdl2Event::MouseMotion {
+            window_id, x, y, ..
+        } => (Some(window_id), InputEvent::MouseMove(x, y)),
+        Sdl2Event::MouseButtonDown {
+            window_id,
+            mouse_btn,
+            x,
+            y,
+            ..
+        } => (
+            Some(window_id),
+            InputEvent::MouseDown {
+                button: to_mouse_button(mouse_btn),
+                position: (x, y),
+            },
+        ),
+        Sdl2Event::MouseButtonUp {
+            window_id,
+            mouse_btn,
+            x,
+            y,
+            ..
+        } => (
+            Some(window_id),
+            InputEvent::MouseUp {
+                button: to_mouse_button(mouse_btn),
+                position: (x, y),
+            },
+        ),
+        Sdl2Event::MouseWheel {
+            window_id,
+            direction,
+            precise_x,
+            precise_y,
+            ..
+        } => {
+            // natural scrolling reports flipped deltas
+            let sign = match direction {
+                MouseWheelDirection::Flipped => -1.0,
+                _ => 1.0,
+            };
+            (
+                Some(window_id),
+                InputEvent::MouseWheel {
+                    x: precise_x * sign,
+                    y: precise_y * sign,
+                },
+            )
+        }
+        Sdl2Event::TextInput {
+            window_id, text, ..
+        } => (Some(window_id), InputEvent::TextInput(text)),
+        Sdl2Event::TextEditing {
+            window_id,
+            text,
+            start,
+            length,
+            ..
+        } => (
+            Some(window_id),
+            InputEvent::TextEditing {
+                text,
+                start,
+                length,
+            },
+        ),
+        Sdl2Event::KeyDown {
+            window_id,
+            keycode,
+            scancode,
+            keymod,
+            repeat,
+            ..
+        } => (
+            Some(window_id),
+            InputEvent::KeyDown {
+                key: keycode.map_or(Key::Unknown, to_key),
+                scancode: scancode.map_or(ScanCode::Unknown, to_scancode),
+                modifiers: to_modifiers(keymod),
+                repeat,
+            },
+        ),
+        Sdl2Event::KeyUp {
+            window_id,
+            keycode,
+            scancode,
+            keymod,
+            ..
+        } => (
+            Some(window_id),
+            InputEvent::KeyUp {
+                key: keycode.map_or(Key::Unknown, to_key),
+                scancode: scancode.map_or(ScanCode::Unknown, to_scancode),
+                modifiers: to_modifiers(keymod),
+            },
+        ),
+        _ => return None,
+    };
+    Some(Event::new(window_id.map(WindowId), event))
+}
+
+// SDL finger positions are normalized to the window they are in
+pub fn to_touch_event(event: &Sdl2Event, window: &Window) -> Option<Event> {
+    let (finger_id, phase, x, y, pressure) = match *event {
+        Sdl2Event::FingerDown {
+            finger_id,
+            x,
+            y,
+            pressure,
+            ..
+        } => (finger_id, TouchPhase::Started, x, y, pressure),
+        Sdl2Event::FingerMotion {
+            finger_id,
+            x,
+            y,
+            pressure,
+            ..
+        } => (finger_id, TouchPhase::Moved, x, y, pressure),
+        Sdl2Event::FingerUp {
+            finger_id,
+            x,
+            y,
+            pressure,
+            ..
+        } => (finger_id, TouchPhase::Ended, x, y, pressure),
+        _ => return None,
+    };
+    let size = window.physical_size();
+    Some(Event::new(
+        Some(window.id()),
+        InputEvent::Touch {
+            finger: FingerId(finger_id),
+            phase,
+            position: (
+                (x * size.x as f32).round() as i32,
+                (y * size.y as f32).round() as i32,
+            ),
+            pressure,
+        },
+    ))
+}
+
+// SDL reports mouse positions and sizes in window coordinates, which are smaller than the
+// drawable on high-DPI displays
+pub fn to_physical(event: InputEvent, scale_factor: f64) -> InputEvent {
+    let scale = |v: i32| (v as f64 * scale_factor).round() as i32;
+    match event {
+        InputEvent::MouseMove(x, y) => InputEvent::MouseMove(scale(x), scale(y)),
+        InputEvent::MouseMotion(x, y) => InputEvent::MouseMotion(scale(x), scale(y)),
+        InputEvent::MouseDown {
+            button,
+            position: (x, y),
+        } => InputEvent::MouseDown {
+            button,
+            position: (scale(x), scale(y)),
+        },
+        InputEvent::MouseUp {
+            button,
+            position: (x, y),
+        } => InputEvent::MouseUp {
+            button,
+            position: (scale(x), scale(y)),
+        },
+        InputEvent::Resized(width, height) => {
+            InputEvent::Resized(scale(width as i32) as u32, scale(height as i32) as u32)
+        }
+        event => event,
+    }
+}
 
 pub fn to_mouse_button(button: Sdl2MouseButton) -> MouseButton {
     match button {
@@ -12,18 +227,154 @@ pub fn to_mouse_button(button: Sdl2MouseButton) -> MouseButton {
     }
 }
 
+pub fn to_modifiers(keymod: Sdl2Mod) -> Modifiers {
+    Modifiers {
+        shift: keymod.intersects(Sdl2Mod::LSHIFTMOD | Sdl2Mod::RSHIFTMOD),
+        ctrl: keymod.intersects(Sdl2Mod::LCTRLMOD | Sdl2Mod::RCTRLMOD),
+        alt: keymod.intersects(Sdl2Mod::LALTMOD | Sdl2Mod::RALTMOD),
+        gui: keymod.intersects(Sdl2Mod::LGUIMOD | Sdl2Mod::RGUIMOD),
+    }
+}
+
+pub fn to_scancode(scancode: Sdl2Scancode) -> ScanCode {
+    match scancode {
+        Sdl2Scancode::A => ScanCode::A,
+        Sdl2Scancode::B => ScanCode::B,
+        Sdl2Scancode::C => ScanCode::C,
+        Sdl2Scancode::D => ScanCode::D,
+        Sdl2Scancode::E => ScanCode::E,
+        Sdl2Scancode::F => ScanCode::F,
+        Sdl2Scancode::G => ScanCode::G,
+        Sdl2Scancode::H => ScanCode::H,
+        Sdl2Scancode::I => ScanCode::I,
+        Sdl2Scancode::J => ScanCode::J,
+        Sdl2Scancode::K => ScanCode::K,
+        Sdl2Scancode::L => ScanCode::L,
+        Sdl2Scancode::M => ScanCode::M,
+        Sdl2Scancode::N => ScanCode::N,
+        Sdl2Scancode::O => ScanCode::O,
+        Sdl2Scancode::P => ScanCode::P,
+        Sdl2Scancode::Q => ScanCode::Q,
+        Sdl2Scancode::R => ScanCode::R,
+        Sdl2Scancode::S => ScanCode::S,
+        Sdl2Scancode::T => ScanCode::T,
+        Sdl2Scancode::U => ScanCode::U,
+        Sdl2Scancode::V => ScanCode::V,
+        Sdl2Scancode::W => ScanCode::W,
+        Sdl2Scancode::X => ScanCode::X,
+        Sdl2Scancode::Y => ScanCode::Y,
+        Sdl2Scancode::Z => ScanCode::Z,
+        Sdl2Scancode::Num1 => ScanCode::Num1,
+        Sdl2Scancode::Num2 => ScanCode::Num2,
+        Sdl2Scancode::Num3 => ScanCode::Num3,
+        Sdl2Scancode::Num4 => ScanCode::Num4,
+        Sdl2Scancode::Num5 => ScanCode::Num5,
+        Sdl2Scancode::Num6 => ScanCode::Num6,
+        Sdl2Scancode::Num7 => ScanCode::Num7,
+        Sdl2Scancode::Num8 => ScanCode::Num8,
+        Sdl2Scancode::Num9 => ScanCode::Num9,
+        Sdl2Scancode::Num0 => ScanCode::Num0,
+        Sdl2Scancode::Return => ScanCode::Return,
+        Sdl2Scancode::Escape => ScanCode::Escape,
+        Sdl2Scancode::Backspace => ScanCode::Backspace,
+        Sdl2Scancode::Tab => ScanCode::Tab,
+        Sdl2Scancode::Space => ScanCode::Space,
+        Sdl2Scancode::Minus => ScanCode::Minus,
+        Sdl2Scancode::Equals => ScanCode::Equals,
+        Sdl2Scancode::LeftBracket => ScanCode::LeftBracket,
+        Sdl2Scancode::RightBracket => ScanCode::RightBracket,
+        Sdl2Scancode::Backslash => ScanCode::Backslash,
+        Sdl2Scancode::NonUsHash => ScanCode::NonUsHash,
+        Sdl2Scancode::Semicolon => ScanCode::Semicolon,
+        Sdl2Scancode::Apostrophe => ScanCode::Apostrophe,
+        Sdl2Scancode::Grave => ScanCode::Grave,
+        Sdl2Scancode::Comma => ScanCode::Comma,
+        Sdl2Scancode::Period => ScanCode::Period,
+        Sdl2Scancode::Slash => ScanCode::Slash,
+        Sdl2Scancode::CapsLock => ScanCode::CapsLock,
+        Sdl2Scancode::F1 => ScanCode::F1,
+        Sdl2Scancode::F2 => ScanCode::F2,
+        Sdl2Scancode::F3 => ScanCode::F3,
+        Sdl2Scancode::F4 => ScanCode::F4,
+        Sdl2Scancode::F5 => ScanCode::F5,
+        Sdl2Scancode::F6 => ScanCode::F6,
+        Sdl2Scancode::F7 => ScanCode::F7,
+        Sdl2Scancode::F8 => ScanCode::F8,
+        Sdl2Scancode::F9 => ScanCode::F9,
+        Sdl2Scancode::F10 => ScanCode::F10,
+        Sdl2Scancode::F11 => ScanCode::F11,
+        Sdl2Scancode::F12 => ScanCode::F12,
+        Sdl2Scancode::PrintScreen => ScanCode::PrintScreen,
+        Sdl2Scancode::ScrollLock => ScanCode::ScrollLock,
+        Sdl2Scancode::Pause => ScanCode::Pause,
+        Sdl2Scancode::Insert => ScanCode::Insert,
+        Sdl2Scancode::Home => ScanCode::Home,
+        Sdl2Scancode::PageUp => ScanCode::PageUp,
+        Sdl2Scancode::Delete => ScanCode::Delete,
+        Sdl2Scancode::End => ScanCode::End,
+        Sdl2Scancode::PageDown => ScanCode::PageDown,
+        Sdl2Scancode::Right => ScanCode::Right,
+        Sdl2Scancode::Left => ScanCode::Left,
+        Sdl2Scancode::Down => ScanCode::Down,
+        Sdl2Scancode::Up => ScanCode::Up,
+        Sdl2Scancode::NumLockClear => ScanCode::NumLockClear,
+        Sdl2Scancode::KpDivide => ScanCode::KpDivide,
+        Sdl2Scancode::KpMultiply => ScanCode::KpMultiply,
+        Sdl2Scancode::KpMinus => ScanCode::KpMinus,
+        Sdl2Scancode::KpPlus => ScanCode::KpPlus,
+        Sdl2Scancode::KpEnter => ScanCode::KpEnter,
+        Sdl2Scancode::Kp1 => ScanCode::Kp1,
+        Sdl2Scancode::Kp2 => ScanCode::Kp2,
+        Sdl2Scancode::Kp3 => ScanCode::Kp3,
+        Sdl2Scancode::Kp4 => ScanCode::Kp4,
+        Sdl2Scancode::Kp5 => ScanCode::Kp5,
+        Sdl2Scancode::Kp6 => ScanCode::Kp6,
+        Sdl2Scancode::Kp7 => ScanCode::Kp7,
+        Sdl2Scancode::Kp8 => ScanCode::Kp8,
+        Sdl2Scancode::Kp9 => ScanCode::Kp9,
+        Sdl2Scancode::Kp0 => ScanCode::Kp0,
+        Sdl2Scancode::KpPeriod => ScanCode::KpPeriod,
+        Sdl2Scancode::NonUsBackslash => ScanCode::NonUsBackslash,
+        Sdl2Scancode::Application => ScanCode::Application,
+        Sdl2Scancode::KpEquals => ScanCode::KpEquals,
+        Sdl2Scancode::F13 => ScanCode::F13,
+        Sdl2Scancode::F14 => ScanCode::F14,
+        Sdl2Scancode::F15 => ScanCode::F15,
+        Sdl2Scancode::F16 => ScanCode::F16,
+        Sdl2Scancode::F17 => ScanCode::F17,
+        Sdl2Scancode::F18 => ScanCode::F18,
+        Sdl2Scancode::F19 => ScanCode::F19,
+        Sdl2Scancode::F20 => ScanCode::F20,
+        Sdl2Scancode::F21 => ScanCode::F21,
+        Sdl2Scancode::F22 => ScanCode::F22,
+        Sdl2Scancode::F23 => ScanCode::F23,
+        Sdl2Scancode::F24 => ScanCode::F24,
+        Sdl2Scancode::LCtrl => ScanCode::LCtrl,
+        Sdl2Scancode::LShift => ScanCode::LShift,
+        Sdl2Scancode::LAlt => ScanCode::LAlt,
+        Sdl2Scancode::LGui => ScanCode::LGui,
+        Sdl2Scancode::RCtrl => ScanCode::RCtrl,
+        Sdl2Scancode::RShift => ScanCode::RShift,
+        Sdl2Scancode::RAlt => ScanCode::RAlt,
+        Sdl2Scancode::RGui => ScanCode::RGui,
+        _ => ScanCode::Unknown,
+    }
+}
+
 pub fn to_key(key_code: Sdl2Keycode) -> Key {
     match key_code {
         Sdl2Keycode::Backspace => Key::Backspace,
         Sdl2Keycode::Space => Key::Space,
         Sdl2Keycode::Tab => Key::Tab,
         Sdl2Keycode::Return => Key::Return,
-        Sdl2Keycode::LShift => Key::Shift,
-        Sdl2Keycode::LCtrl => Key::Ctrl,
-        Sdl2Keycode::LAlt => Key::Alt,
-        Sdl2Keycode::RShift => Key::Shift,
-        Sdl2Keycode::RCtrl => Key::Ctrl,
-        Sdl2Keycode::RAlt => Key::Alt,
+        Sdl2Keycode::LShift => Key::LShift,
+        Sdl2Keycode::LCtrl => Key::LCtrl,
+        Sdl2Keycode::LAlt => Key::LAlt,
+        Sdl2Keycode::LGui => Key::LGui,
+        Sdl2Keycode::RShift => Key::RShift,
+        Sdl2Keycode::RCtrl => Key::RCtrl,
+        Sdl2Keycode::RAlt => Key::RAlt,
+        Sdl2Keycode::RGui => Key::RGui,
         Sdl2Keycode::Pause => Key::Pause,
         Sdl2Keycode::CapsLock => Key::CapsLock,
         Sdl2Keycode::Escape => Key::Escape,
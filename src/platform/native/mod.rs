@@ -1,123 +1,150 @@
 pub mod audio;
+pub mod clipboard;
+mod gamepad;
 mod input;
 pub mod rand;
 pub mod rendering;
 //pub mod websocket;
 pub mod window;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use failure::Error;
 use sdl2;
-use sdl2::event::Event;
 use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 
-use input::{Input, InputEvent};
-use window::WindowSettings;
+use app::ControlFlow;
+use input::{Event, InputEvent};
+use window::{FramePacing, WindowId, WindowSettings};
 
-use self::input::{to_key, to_mouse_button};
+use self::gamepad::Gamepads;
+use self::input::{to_physical, to_touch_event};
 use self::window::Window;
+use super::MAX_FRAME_DT;
 
 pub struct Context {
     video: sdl2::VideoSubsystem,
     audio: sdl2::AudioSubsystem,
     windows: Rc<RefCell<Vec<Rc<Window>>>>,
+    frame_pacing: Rc<Cell<Option<FramePacing>>>,
 }
 
 impl Context {
     pub fn window(&mut self, settings: WindowSettings) -> Result<Rc<Window>, Error> {
+        let frame_pacing = FramePacing::shared(self.frame_pacing.get(), settings.frame_pacing)?;
+        let settings = WindowSettings {
+            frame_pacing: Some(frame_pacing),
+            ..settings
+        };
         let window = Rc::new(Window::new(self, settings)?);
+        self.frame_pacing.set(Some(frame_pacing));
         self.windows.borrow_mut().push(window.clone());
         Ok(window)
     }
 }
 
-pub fn init<F: FnOnce(Context) -> T, T: FnMut(f64, &Input) -> Result<(), Error> + 'static>(
+pub fn init<F: FnOnce(Context) -> T, T: FnMut(f64, Vec<Event>) -> ControlFlow + 'static>(
     app_factory: F,
 ) {
+    // touch to mouse emulation is up to each window's `touch_as_mouse` setting
+    sdl2::hint::set("SDL_TOUCH_MOUSE_EVENTS", "0");
     let sdl_context = sdl2::init().unwrap();
     let sdl_video = sdl_context.video().unwrap();
+    // SDL starts with text input enabled, it stays off until a window asks for it
+    sdl_video.text_input().stop();
 
-    let refresh_rate = sdl_video
-        .current_display_mode(0)
-        .expect("could not get display mode for current display")
-        .refresh_rate;
-
+    let timer = sdl_context.timer().unwrap();
+    let mut gamepads = Gamepads::new(sdl_context.game_controller().unwrap());
+    let keyboard = sdl_context.keyboard();
     let mut event_pump = sdl_context.event_pump().unwrap();
-    let mut input = Input::new();
 
     let windows = Rc::new(RefCell::new(Vec::new()));
+    let frame_pacing = Rc::new(Cell::new(None));
     let context = Context {
         video: sdl_video,
         audio: sdl_context.audio().unwrap(),
         windows: windows.clone(),
+        frame_pacing: frame_pacing.clone(),
     };
 
     let mut main_loop = app_factory(context);
     let mut start_frame = Instant::now();
-    let mut frame_delay = 0.0;
-    let target_frame_time = 1.0 / refresh_rate as f64;
+    let mut next_frame = start_frame;
     'main: loop {
         let frame_elapsed = start_frame.elapsed();
         start_frame = Instant::now();
-        let frame_dt = frame_elapsed.as_secs() as f64
-            + (frame_elapsed.subsec_nanos() as f64 / 1_000_000_000.0);
-        frame_delay += target_frame_time - frame_dt;
-
-        let mut input_events = Vec::new();
-        for event in event_pump.poll_iter() {
-            match event {
-                Event::MouseMotion { x, y, .. } => {
-                    input_events.push(InputEvent::MouseMove(x, y));
-                }
-                Event::MouseButtonDown {
-                    mouse_btn, x, y, ..
-                } => {
-                    input_events.push(InputEvent::MouseDown {
-                        button: to_mouse_button(mouse_btn),
-                        position: (x, y),
-                    });
-                }
-                Event::MouseButtonUp {
-                    mouse_btn, x, y, ..
-                } => {
-                    input_events.push(InputEvent::MouseUp {
-                        button: to_mouse_button(mouse_btn),
-                        position: (x, y),
-                    });
-                }
-                Event::KeyDown {
-                    keycode: Some(key), ..
-                } => input_events.push(InputEvent::KeyDown(to_key(key))),
-                Event::KeyUp {
-                    keycode: Some(key), ..
-                } => input_events.push(InputEvent::KeyUp(to_key(key))),
-                _ => {}
+        let frame_dt = as_secs(frame_elapsed);
+
+        // finger events don't say which window they are in, they go to the focused one
+        let touch_window = keyboard.focused_window_id().and_then(|id| {
+            windows
+                .borrow()
+                .iter()
+                .find(|w| w.id() == WindowId(id))
+                .cloned()
+        });
+        let mut input_events: Vec<Event> = event_pump
+            .poll_iter()
+            .flat_map(|e| {
+                // SDL timestamps are milliseconds since SDL was initialized
+                let time = e.get_timestamp() as f64 / 1000.0;
+                let events = match touch_window.as_ref().and_then(|w| to_touch_event(&e, w)) {
+                    Some(touch) => vec![touch],
+                    None => gamepads.to_events(e),
+                };
+                events.into_iter().map(move |e| e.time(time))
+            })
+            .collect();
+        input_events.extend(super::close_dropped_windows(
+            &windows,
+            timer.ticks() as f64 / 1000.0,
+        ));
+        for w in windows.borrow().iter() {
+            for e in input_events.iter_mut().filter(|e| e.window == Some(w.id())) {
+                e.event = to_physical(e.event.clone(), w.scale_factor());
             }
+            if let Some(scale_factor) = w.update_scale_factor() {
+                input_events.push(
+                    Event::new(Some(w.id()), InputEvent::ScaleFactorChanged(scale_factor))
+                        .time(timer.ticks() as f64 / 1000.0),
+                );
+            }
+            input_events = w.synthesize_mouse_events(input_events);
         }
-        input.update(input_events);
-
-        let start_update = Instant::now();
-        main_loop(frame_dt, &input).unwrap();
-        let update_elapsed = start_update.elapsed();
-        let update_dt = update_elapsed.as_secs() as f64
-            + (update_elapsed.subsec_nanos() as f64 / 1_000_000_000.0);
-
-        let sleep = target_frame_time - update_dt + frame_delay - 0.002;
-        if sleep > 0.0 {
-            thread::sleep(Duration::new(
-                sleep.floor() as u64,
-                (sleep * 1_000_000_000.0).floor() as u32,
-            ));
-        } else {
-            frame_delay = 0.0;
+        if main_loop(frame_dt.min(MAX_FRAME_DT), input_events) == ControlFlow::Exit {
+            break 'main;
         }
 
         for w in windows.borrow().iter() {
             w.gl_finish();
         }
+
+        // vsync waits for the display on its own, only a capped frame rate sleeps
+        if let Some(FramePacing::Capped(fps)) = frame_pacing.get() {
+            // frames are scheduled on a fixed cadence, unless we fall more than a frame behind
+            let target_frame_time = from_secs(1.0 / fps);
+            next_frame += target_frame_time;
+            let now = Instant::now();
+            if next_frame > now {
+                thread::sleep(next_frame - now);
+            } else if now - next_frame > target_frame_time {
+                next_frame = now;
+            }
+        }
     }
+
+    // tear down the app before the windows and GL contexts it renders to
+    drop(main_loop);
+    windows.borrow_mut().clear();
+}
+
+fn as_secs(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + (duration.subsec_nanos() as f64 / 1_000_000_000.0)
+}
+
+fn from_secs(secs: f64) -> Duration {
+    Duration::new(secs.trunc() as u64, (secs.fract() * 1_000_000_000.0) as u32)
 }
@@ -1,6 +1,7 @@
 use gl;
 use gl::types::*;
 use std;
+use std::collections::HashMap;
 use std::ffi::CString;
 use std::mem;
 use std::os::raw::c_void;
@@ -9,9 +10,21 @@ use std::ptr;
 use failure::Error;
 
 use assets::Image;
-use rendering::{TextureFiltering, Vertex, VertexAttributeType};
+use rendering::{
+    BlendEquation, BlendFactor, CullFace, DrawRange, Index, IndexType, Primitive, RenderState,
+    TextureFiltering, Vertex, VertexAttributeType,
+};
 
 pub type VertexBuffer = (u32, u32);
+pub struct IndexBuffer(GLuint);
+
+impl Drop for IndexBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.0);
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Texture {
@@ -72,33 +85,80 @@ impl Texture {
 }
 
 pub struct Program {
-    uniforms: Vec<(String, Uniform)>,
+    uniforms: HashMap<String, Uniform>,
+    // locations of the program's active uniforms and attributes, looked up once after linking
+    uniform_locations: HashMap<String, GLint>,
+    attrib_locations: HashMap<String, GLint>,
     gl_ref: GLuint,
 }
 
 #[derive(Clone)]
 pub enum Uniform {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
     Vec2((f32, f32)),
+    Vec3((f32, f32, f32)),
+    Vec4((f32, f32, f32, f32)),
+    Mat2([f32; 4]),
+    Mat3([f32; 9]),
+    Mat4([f32; 16]),
+    FloatArray(Vec<f32>),
+    IntArray(Vec<i32>),
+    Vec2Array(Vec<(f32, f32)>),
+    Vec3Array(Vec<(f32, f32, f32)>),
+    Vec4Array(Vec<(f32, f32, f32, f32)>),
     Texture(Texture),
 }
 
 impl Program {
     fn new(vertex_shader: GLVertexShader, frag_shader: GLFragmentShader) -> Result<Program, Error> {
-        Ok(Program {
-            uniforms: Vec::new(),
-            gl_ref: link_program(vertex_shader.gl_ref(), frag_shader.gl_ref())?,
-        })
+        let gl_ref = link_program(vertex_shader.gl_ref(), frag_shader.gl_ref())?;
+        unsafe {
+            Ok(Program {
+                uniforms: HashMap::new(),
+                uniform_locations: active_locations(
+                    gl_ref,
+                    gl::ACTIVE_UNIFORMS,
+                    gl::ACTIVE_UNIFORM_MAX_LENGTH,
+                    gl::GetActiveUniform,
+                    gl::GetUniformLocation,
+                )?,
+                attrib_locations: active_locations(
+                    gl_ref,
+                    gl::ACTIVE_ATTRIBUTES,
+                    gl::ACTIVE_ATTRIBUTE_MAX_LENGTH,
+                    gl::GetActiveAttrib,
+                    gl::GetAttribLocation,
+                )?,
+                gl_ref,
+            })
+        }
     }
     fn gl_ref(&self) -> GLuint {
         self.gl_ref
     }
 
-    pub fn set_uniform(&mut self, name: &str, uniform: Uniform) {
-        self.uniforms.push((name.into(), uniform));
+    // Uniforms the shader compiler optimized out are not active and can't be set either
+    pub fn set_uniform(&mut self, name: &str, uniform: Uniform) -> Result<(), Error> {
+        if !self.uniform_locations.contains_key(name) {
+            return Err(format_err!("no active uniform named {} in program", name));
+        }
+        self.uniforms.insert(name.into(), uniform);
+        Ok(())
     }
-    pub fn uniforms(&self) -> impl Iterator<Item = &(String, Uniform)> {
+    pub fn uniforms(&self) -> impl Iterator<Item = (&String, &Uniform)> {
         self.uniforms.iter()
     }
+    fn uniform_location(&self, name: &str) -> GLint {
+        self.uniform_locations[name]
+    }
+    // `None` for attributes the shader compiler optimized out
+    fn attrib_location(&self, name: &str) -> Option<GLuint> {
+        self.attrib_locations
+            .get(name)
+            .map(|&location| location as GLuint)
+    }
 }
 
 pub fn screen_size() -> (i32, i32) {
@@ -119,6 +179,15 @@ pub fn create_vertex_buffer() -> Result<(GLuint, GLuint), Error> {
 
     Ok((vao, vbo))
 }
+pub fn create_index_buffer() -> Result<IndexBuffer, Error> {
+    let mut ebo = 0;
+
+    unsafe {
+        gl::GenBuffers(1, &mut ebo);
+    }
+
+    Ok(IndexBuffer(ebo))
+}
 pub fn create_program(vs: &str, fs: &str) -> Result<Program, Error> {
     let vs = GLVertexShader::new(vs)?;
     let fs = GLFragmentShader::new(fs)?;
@@ -137,16 +206,8 @@ pub fn create_texture(
     Ok(Texture::new(size, filtering))
 }
 
-pub fn render_vertices<V: Vertex>(
-    vertex_buffer: &(GLuint, GLuint),
-    program: &Program,
-    vertices: &Vec<V>,
-) -> Result<(), Error> {
+pub fn upload_vertices<V: Vertex>(vertex_buffer: &(GLuint, GLuint), vertices: &Vec<V>) {
     unsafe {
-        gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
-        gl::Enable(gl::BLEND);
-
-        // push vertex data
         let &(vao, vbo) = vertex_buffer;
         gl::BindVertexArray(vao);
 
@@ -157,20 +218,202 @@ pub fn render_vertices<V: Vertex>(
             mem::transmute(vertices.as_ptr()),
             gl::STATIC_DRAW,
         );
+    }
+}
+
+pub fn upload_indices<I: Index>(
+    vertex_buffer: &(GLuint, GLuint),
+    index_buffer: &IndexBuffer,
+    indices: &Vec<I>,
+) {
+    unsafe {
+        // the element buffer binding is part of the vertex array state
+        gl::BindVertexArray(vertex_buffer.0);
+
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, index_buffer.0);
+        gl::BufferData(
+            gl::ELEMENT_ARRAY_BUFFER,
+            (indices.len() * I::index_type().size()) as GLsizeiptr,
+            indices.as_ptr() as *const c_void,
+            gl::STATIC_DRAW,
+        );
+    }
+}
+
+pub fn draw_vertices<V: Vertex>(
+    vertex_buffer: &(GLuint, GLuint),
+    program: &Program,
+    state: &RenderState,
+    range: DrawRange,
+) -> Result<(), Error> {
+    setup_draw::<V>(vertex_buffer, program, state)?;
+    unsafe {
+        gl::DrawArrays(
+            to_gl_primitive(range.primitive),
+            range.first as GLint,
+            range.count as GLsizei,
+        );
+    }
+
+    Ok(())
+}
+
+pub fn draw_indexed<V: Vertex>(
+    vertex_buffer: &(GLuint, GLuint),
+    index_buffer: &IndexBuffer,
+    program: &Program,
+    state: &RenderState,
+    index_type: IndexType,
+    range: DrawRange,
+) -> Result<(), Error> {
+    setup_draw::<V>(vertex_buffer, program, state)?;
+    unsafe {
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, index_buffer.0);
+
+        let gl_index_type = match index_type {
+            IndexType::UnsignedShort => gl::UNSIGNED_SHORT,
+            IndexType::UnsignedInt => gl::UNSIGNED_INT,
+        };
+        gl::DrawElements(
+            to_gl_primitive(range.primitive),
+            range.count as GLsizei,
+            gl_index_type,
+            (range.first * index_type.size()) as *const c_void,
+        );
+    }
+
+    Ok(())
+}
+
+unsafe fn apply_render_state(state: &RenderState) {
+    match state.blend.funcs() {
+        Some((color, alpha)) => {
+            gl::Enable(gl::BLEND);
+            gl::BlendFuncSeparate(
+                to_gl_blend_factor(color.src),
+                to_gl_blend_factor(color.dst),
+                to_gl_blend_factor(alpha.src),
+                to_gl_blend_factor(alpha.dst),
+            );
+            gl::BlendEquationSeparate(
+                to_gl_blend_equation(color.equation),
+                to_gl_blend_equation(alpha.equation),
+            );
+        }
+        None => gl::Disable(gl::BLEND),
+    }
+
+    let mask = state.color_mask;
+    gl::ColorMask(
+        mask.r as GLboolean,
+        mask.g as GLboolean,
+        mask.b as GLboolean,
+        mask.a as GLboolean,
+    );
+
+    match state.cull {
+        Some(face) => {
+            gl::Enable(gl::CULL_FACE);
+            gl::CullFace(match face {
+                CullFace::Front => gl::FRONT,
+                CullFace::Back => gl::BACK,
+                CullFace::FrontAndBack => gl::FRONT_AND_BACK,
+            });
+        }
+        None => gl::Disable(gl::CULL_FACE),
+    }
+}
+
+fn to_gl_blend_factor(factor: BlendFactor) -> GLenum {
+    match factor {
+        BlendFactor::Zero => gl::ZERO,
+        BlendFactor::One => gl::ONE,
+        BlendFactor::SrcColor => gl::SRC_COLOR,
+        BlendFactor::OneMinusSrcColor => gl::ONE_MINUS_SRC_COLOR,
+        BlendFactor::DstColor => gl::DST_COLOR,
+        BlendFactor::OneMinusDstColor => gl::ONE_MINUS_DST_COLOR,
+        BlendFactor::SrcAlpha => gl::SRC_ALPHA,
+        BlendFactor::OneMinusSrcAlpha => gl::ONE_MINUS_SRC_ALPHA,
+        BlendFactor::DstAlpha => gl::DST_ALPHA,
+        BlendFactor::OneMinusDstAlpha => gl::ONE_MINUS_DST_ALPHA,
+    }
+}
+
+fn to_gl_blend_equation(equation: BlendEquation) -> GLenum {
+    match equation {
+        BlendEquation::Add => gl::FUNC_ADD,
+        BlendEquation::Subtract => gl::FUNC_SUBTRACT,
+        BlendEquation::ReverseSubtract => gl::FUNC_REVERSE_SUBTRACT,
+    }
+}
+
+fn to_gl_primitive(primitive: Primitive) -> GLenum {
+    match primitive {
+        Primitive::Points => gl::POINTS,
+        Primitive::Lines => gl::LINES,
+        Primitive::LineStrip => gl::LINE_STRIP,
+        Primitive::LineLoop => gl::LINE_LOOP,
+        Primitive::Triangles => gl::TRIANGLES,
+        Primitive::TriangleStrip => gl::TRIANGLE_STRIP,
+        Primitive::TriangleFan => gl::TRIANGLE_FAN,
+    }
+}
+
+// Binds the vertex buffer, program and uniforms a draw call of the buffer's vertices needs
+fn setup_draw<V: Vertex>(
+    vertex_buffer: &(GLuint, GLuint),
+    program: &Program,
+    state: &RenderState,
+) -> Result<(), Error> {
+    unsafe {
+        apply_render_state(state);
+
+        let &(vao, vbo) = vertex_buffer;
+        gl::BindVertexArray(vao);
+        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
 
         gl::UseProgram(program.gl_ref());
 
         // set uniforms
         let mut texture_index = 0;
-        for &(ref name, ref uniform) in program.uniforms() {
-            let attr = gl::GetUniformLocation(
-                program.gl_ref(),
-                CString::new(name.clone().into_bytes()).unwrap().as_ptr(),
-            );
+        for (name, uniform) in program.uniforms() {
+            let attr = program.uniform_location(name);
             match uniform {
+                &Uniform::Float(v) => gl::Uniform1f(attr, v),
+                &Uniform::Int(v) => gl::Uniform1i(attr, v),
+                &Uniform::Bool(v) => gl::Uniform1i(attr, v as GLint),
                 &Uniform::Vec2(gl_vec2) => {
                     gl::Uniform2f(attr, gl_vec2.0 as GLfloat, gl_vec2.1 as GLfloat)
                 }
+                &Uniform::Vec3((x, y, z)) => gl::Uniform3f(attr, x, y, z),
+                &Uniform::Vec4((x, y, z, w)) => gl::Uniform4f(attr, x, y, z, w),
+                &Uniform::Mat2(ref m) => gl::UniformMatrix2fv(attr, 1, gl::FALSE, m.as_ptr()),
+                &Uniform::Mat3(ref m) => gl::UniformMatrix3fv(attr, 1, gl::FALSE, m.as_ptr()),
+                &Uniform::Mat4(ref m) => gl::UniformMatrix4fv(attr, 1, gl::FALSE, m.as_ptr()),
+                &Uniform::FloatArray(ref v) => gl::Uniform1fv(attr, v.len() as GLsizei, v.as_ptr()),
+                &Uniform::IntArray(ref v) => gl::Uniform1iv(attr, v.len() as GLsizei, v.as_ptr()),
+                // tuples have no guaranteed layout, vector arrays are flattened first
+                &Uniform::Vec2Array(ref v) => {
+                    let mut flat = Vec::with_capacity(v.len() * 2);
+                    for &(x, y) in v {
+                        flat.extend_from_slice(&[x, y]);
+                    }
+                    gl::Uniform2fv(attr, v.len() as GLsizei, flat.as_ptr())
+                }
+                &Uniform::Vec3Array(ref v) => {
+                    let mut flat = Vec::with_capacity(v.len() * 3);
+                    for &(x, y, z) in v {
+                        flat.extend_from_slice(&[x, y, z]);
+                    }
+                    gl::Uniform3fv(attr, v.len() as GLsizei, flat.as_ptr())
+                }
+                &Uniform::Vec4Array(ref v) => {
+                    let mut flat = Vec::with_capacity(v.len() * 4);
+                    for &(x, y, z, w) in v {
+                        flat.extend_from_slice(&[x, y, z, w]);
+                    }
+                    gl::Uniform4fv(attr, v.len() as GLsizei, flat.as_ptr())
+                }
                 &Uniform::Texture(ref gl_texture) => {
                     gl::ActiveTexture(gl::TEXTURE0 + texture_index);
                     gl::BindTexture(gl::TEXTURE_2D, gl_texture.gl_ref());
@@ -183,36 +426,35 @@ pub fn render_vertices<V: Vertex>(
         // define vertex format
         let mut step = 0;
         for (attr_name, attr_count, attr_type) in V::attributes() {
-            let attr =
-                gl::GetAttribLocation(program.gl_ref(), CString::new(attr_name).unwrap().as_ptr());
-            gl::EnableVertexAttribArray(attr as GLuint);
-            match attr_type {
-                VertexAttributeType::Float => {
-                    gl::VertexAttribPointer(
-                        attr as GLuint,
-                        attr_count as GLsizei,
-                        gl::FLOAT,
-                        gl::FALSE as GLboolean,
-                        V::stride() as GLsizei,
-                        step as *const c_void,
-                    );
-                }
-                VertexAttributeType::Unsigned => {
-                    gl::VertexAttribPointer(
-                        attr as GLuint,
-                        attr_count as GLsizei,
-                        gl::UNSIGNED_INT,
-                        gl::FALSE as GLboolean,
-                        V::stride() as GLsizei,
-                        step as *const c_void,
-                    );
+            // attributes missing from the program are skipped, their data is still in the stride
+            if let Some(attr) = program.attrib_location(&attr_name) {
+                gl::EnableVertexAttribArray(attr);
+                match attr_type {
+                    VertexAttributeType::Float => {
+                        gl::VertexAttribPointer(
+                            attr,
+                            attr_count as GLsizei,
+                            gl::FLOAT,
+                            gl::FALSE as GLboolean,
+                            V::stride() as GLsizei,
+                            step as *const c_void,
+                        );
+                    }
+                    VertexAttributeType::Unsigned => {
+                        gl::VertexAttribPointer(
+                            attr,
+                            attr_count as GLsizei,
+                            gl::UNSIGNED_INT,
+                            gl::FALSE as GLboolean,
+                            V::stride() as GLsizei,
+                            step as *const c_void,
+                        );
+                    }
                 }
             }
 
             step += attr_count * attr_type.size();
         }
-
-        gl::DrawArrays(gl::TRIANGLES, 0, vertices.len() as GLsizei);
     }
 
     Ok(())
@@ -221,6 +463,8 @@ pub fn render_vertices<V: Vertex>(
 pub fn clear(color: Option<(f32, f32, f32, f32)>) {
     let (r, g, b, a) = color.unwrap_or((0.0, 0.0, 0.0, 1.0));
     unsafe {
+        // clearing obeys the color mask, which draws leave set
+        gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
         gl::ClearColor(r, g, b, a);
         gl::Clear(gl::COLOR_BUFFER_BIT);
     }
@@ -316,3 +560,52 @@ fn link_program(vs: GLuint, fs: GLuint) -> Result<GLuint, Error> {
     }
     Ok(program)
 }
+
+type GetActive =
+    unsafe fn(GLuint, GLuint, GLsizei, *mut GLsizei, *mut GLint, *mut GLenum, *mut GLchar);
+type GetLocation = unsafe fn(GLuint, *const GLchar) -> GLint;
+
+// Looks up the location of every active uniform or attribute of a linked program
+unsafe fn active_locations(
+    program: GLuint,
+    count_param: GLenum,
+    max_length_param: GLenum,
+    get_active: GetActive,
+    get_location: GetLocation,
+) -> Result<HashMap<String, GLint>, Error> {
+    let mut count = 0;
+    gl::GetProgramiv(program, count_param, &mut count);
+    let mut max_length = 0;
+    gl::GetProgramiv(program, max_length_param, &mut max_length);
+
+    let mut locations = HashMap::new();
+    for index in 0..count as GLuint {
+        let mut name = vec![0; max_length as usize];
+        let mut length = 0;
+        let mut size = 0;
+        let mut data_type = 0;
+        get_active(
+            program,
+            index,
+            max_length,
+            &mut length,
+            &mut size,
+            &mut data_type,
+            name.as_mut_ptr() as *mut GLchar,
+        );
+        name.truncate(length as usize);
+        let location = get_location(program, CString::new(name.clone())?.as_ptr());
+        locations.insert(variable_name(&String::from_utf8(name)?), location);
+    }
+    Ok(locations)
+}
+
+// Arrays are reported by their first element, "lights[0]", but set by their plain name. Members
+// of arrays of structs, "lights[0].color", keep their index.
+fn variable_name(name: &str) -> String {
+    if name.ends_with("[0]") {
+        name[..name.len() - 3].into()
+    } else {
+        name.into()
+    }
+}
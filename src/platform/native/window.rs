@@ -1,26 +1,64 @@
+use std::cell::{Cell, RefCell};
+
 use failure::Error;
 use gl;
+use gl::types::*;
 use sdl2;
-use sdl2::video::{GLProfile, SwapInterval};
+use sdl2::mouse::Cursor as Sdl2Cursor;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+use sdl2::video::{FullscreenType, GLProfile, SwapInterval};
 
 use super::Context;
-use window::WindowSettings;
+use input::Event;
+use math::Vec2;
+use platform::touch::TouchMouse;
+use window::{Cursor, FramePacing, Fullscreen, WindowId, WindowSettings};
 
-pub struct Window(sdl2::video::Window);
+pub struct Window {
+    window: RefCell<sdl2::video::Window>,
+    viewport_size: Cell<(u32, u32)>,
+    scale_factor: Cell<f64>,
+    // SDL only keeps a pointer to the active cursor, it has to outlive its use
+    cursor: RefCell<Option<Sdl2Cursor>>,
+    touch_mouse: Option<TouchMouse>,
+}
 
 pub struct GLContext(sdl2::video::GLContext);
 
 impl Window {
     pub fn new(context: &mut Context, settings: WindowSettings) -> Result<Window, Error> {
-        let WindowSettings { title, size, .. } = settings;
+        let WindowSettings {
+            title,
+            size,
+            resizable,
+            fullscreen,
+            min_size,
+            touch_as_mouse,
+            frame_pacing,
+            ..
+        } = settings;
 
         let title = title.ok_or_else(|| format_err!("missing title in WindowSettings"))?;
         let size = size.ok_or_else(|| format_err!("missing size in WindowSettings"))?;
-        let window = context
-            .video
-            .window(&title, size.x, size.y)
-            .opengl()
-            .build()?;
+        let mut builder = context.video.window(&title, size.x, size.y);
+        builder.opengl().allow_highdpi();
+        if resizable {
+            builder.resizable();
+        }
+        match fullscreen {
+            Some(Fullscreen::Exclusive) => {
+                builder.fullscreen();
+            }
+            Some(Fullscreen::Borderless) => {
+                builder.fullscreen_desktop();
+            }
+            None => {}
+        }
+        let mut window = builder.build()?;
+        if let Some(min_size) = min_size {
+            window.set_minimum_size(min_size.x, min_size.y)?;
+        }
 
         let gl_attr = context.video.gl_attr();
         gl_attr.set_context_major_version(2);
@@ -31,30 +69,179 @@ impl Window {
 
         let _gl_context = window.gl_create_context();
         gl::load_with(|name| context.video.gl_get_proc_address(name) as *const _);
-        context
-            .video
-            .gl_set_swap_interval(SwapInterval::VSync)
-            .map_err(|e| format_err!("{}", e))?;
+        let video = &context.video;
+        match frame_pacing.unwrap_or_default() {
+            FramePacing::VSync => video.gl_set_swap_interval(SwapInterval::VSync),
+            FramePacing::AdaptiveVSync => video
+                .gl_set_swap_interval(SwapInterval::LateSwapTearing)
+                .or_else(|_| video.gl_set_swap_interval(SwapInterval::VSync)),
+            FramePacing::Capped(_) | FramePacing::Uncapped => {
+                video.gl_set_swap_interval(SwapInterval::Immediate)
+            }
+        }
+        .map_err(|e| format_err!("{}", e))?;
 
-        Ok(Window(window))
+        let id = WindowId(window.id());
+        let window = Window {
+            window: RefCell::new(window),
+            viewport_size: Cell::new((0, 0)),
+            scale_factor: Cell::new(1.0),
+            cursor: RefCell::new(None),
+            touch_mouse: if touch_as_mouse {
+                Some(TouchMouse::new(id))
+            } else {
+                None
+            },
+        };
+        window.scale_factor.set(window.scale_factor());
+        Ok(window)
+    }
+
+    pub fn id(&self) -> WindowId {
+        WindowId(self.window.borrow().id())
+    }
+
+    pub fn synthesize_mouse_events(&self, events: Vec<Event>) -> Vec<Event> {
+        match self.touch_mouse {
+            Some(ref touch_mouse) => touch_mouse.synthesize(events),
+            None => events,
+        }
+    }
+
+    pub fn size(&self) -> Vec2<u32> {
+        let (width, height) = self.window.borrow().size();
+        Vec2::new(width, height)
+    }
+
+    pub fn physical_size(&self) -> Vec2<u32> {
+        let (width, height) = self.window.borrow().drawable_size();
+        Vec2::new(width, height)
+    }
+
+    // SDL has no event for this, on high-DPI displays the drawable is simply larger than the
+    // window
+    pub fn scale_factor(&self) -> f64 {
+        let window = self.window.borrow();
+        let (width, _) = window.size();
+        let (drawable_width, _) = window.drawable_size();
+        if width > 0 {
+            drawable_width as f64 / width as f64
+        } else {
+            self.scale_factor.get()
+        }
+    }
+
+    // Returns the new scale factor if it changed since the last call, e.g. after the window
+    // moved to another display
+    pub fn update_scale_factor(&self) -> Option<f64> {
+        let scale_factor = self.scale_factor();
+        if scale_factor != self.scale_factor.get() {
+            self.scale_factor.set(scale_factor);
+            Some(scale_factor)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_size(&self, size: Vec2<u32>) -> Result<(), Error> {
+        self.window.borrow_mut().set_size(size.x, size.y)?;
+        Ok(())
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) -> Result<(), Error> {
+        let fullscreen = match fullscreen {
+            Some(Fullscreen::Exclusive) => FullscreenType::True,
+            Some(Fullscreen::Borderless) => FullscreenType::Desktop,
+            None => FullscreenType::Off,
+        };
+        self.window
+            .borrow_mut()
+            .set_fullscreen(fullscreen)
+            .map_err(|e| format_err!("{}", e))
+    }
+
+    // the cursor is shared by all windows in SDL, visibility, relative mode and the cursor image
+    // apply to the whole application
+    pub fn set_cursor_visible(&self, visible: bool) {
+        self.mouse().show_cursor(visible);
+    }
+
+    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), Error> {
+        self.window.borrow_mut().set_mouse_grab(grab);
+        Ok(())
+    }
+
+    pub fn set_relative_mouse_mode(&self, relative: bool) -> Result<(), Error> {
+        let mouse = self.mouse();
+        mouse.set_relative_mouse_mode(relative);
+        if mouse.relative_mouse_mode() != relative {
+            return Err(format_err!("relative mouse mode is not supported"));
+        }
+        Ok(())
+    }
+
+    pub fn set_cursor(&self, cursor: Option<&Cursor>) -> Result<(), Error> {
+        let sdl_cursor = match cursor {
+            Some(cursor) => {
+                let mut data = cursor.image.data.clone();
+                let (width, height) = (cursor.image.width, cursor.image.height);
+                let surface = Surface::from_data(
+                    &mut data,
+                    width,
+                    height,
+                    width * 4,
+                    PixelFormatEnum::RGBA32,
+                )
+                .map_err(|e| format_err!("{}", e))?;
+                Sdl2Cursor::from_surface(surface, cursor.hotspot.x as i32, cursor.hotspot.y as i32)
+                    .map_err(|e| format_err!("{}", e))?
+            }
+            None => Sdl2Cursor::from_system(sdl2::mouse::SystemCursor::Arrow)
+                .map_err(|e| format_err!("{}", e))?,
+        };
+        sdl_cursor.set();
+        *self.cursor.borrow_mut() = Some(sdl_cursor);
+        Ok(())
+    }
+
+    // like the cursor, text input is global in SDL
+    pub fn start_text_input(&self) {
+        self.window.borrow().subsystem().text_input().start();
+    }
+
+    pub fn stop_text_input(&self) {
+        self.window.borrow().subsystem().text_input().stop();
+    }
+
+    fn mouse(&self) -> sdl2::mouse::MouseUtil {
+        self.window.borrow().subsystem().sdl().mouse()
     }
 
     pub fn gl_create_context(&self) -> GLContext {
         GLContext(
-            self.0
+            self.window
+                .borrow()
                 .gl_create_context()
                 .expect("could not create gl context"),
         )
     }
 
     pub fn gl_set_current(&self, gl_context: &GLContext) {
-        self.0
+        let window = self.window.borrow();
+        window
             .gl_make_current(&gl_context.0)
-            .expect("could not set window as current gl context")
+            .expect("could not set window as current gl context");
+
+        // keep the viewport covering the window as it gets resized
+        let drawable_size = window.drawable_size();
+        if drawable_size != self.viewport_size.get() {
+            unsafe { gl::Viewport(0, 0, drawable_size.0 as GLsizei, drawable_size.1 as GLsizei) };
+            self.viewport_size.set(drawable_size);
+        }
     }
 
     pub fn gl_finish(&self) {
-        self.0.gl_swap_window();
+        self.window.borrow().gl_swap_window();
         unsafe { gl::Finish() };
     }
 }
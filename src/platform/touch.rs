@@ -0,0 +1,80 @@
+use std::cell::Cell;
+
+use input::{Event, FingerId, InputEvent, MouseButton, TouchPhase};
+use window::WindowId;
+
+// Drives the left mouse button and mouse position of a window from the first finger that
+// touches it, so apps that only handle the mouse work on touch screens
+pub struct TouchMouse {
+    window: WindowId,
+    primary: Cell<Option<FingerId>>,
+}
+
+impl TouchMouse {
+    pub fn new(window: WindowId) -> TouchMouse {
+        TouchMouse {
+            window,
+            primary: Cell::new(None),
+        }
+    }
+
+    // Adds mouse events after each of the primary finger's touch events
+    pub fn synthesize(&self, events: Vec<Event>) -> Vec<Event> {
+        let mut out = Vec::with_capacity(events.len());
+        for e in events {
+            let time = e.time;
+            let mouse_events = match e {
+                Event {
+                    window: Some(window),
+                    event:
+                        InputEvent::Touch {
+                            finger,
+                            phase,
+                            position,
+                            ..
+                        },
+                    ..
+                } if window == self.window => self.to_mouse_events(finger, phase, position),
+                _ => Vec::new(),
+            };
+            out.push(e);
+            out.extend(
+                mouse_events
+                    .into_iter()
+                    .map(|m| Event::new(Some(self.window), m).time(time)),
+            );
+        }
+        out
+    }
+
+    fn to_mouse_events(
+        &self,
+        finger: FingerId,
+        phase: TouchPhase,
+        position: (i32, i32),
+    ) -> Vec<InputEvent> {
+        let button = MouseButton::Left;
+        match (phase, self.primary.get()) {
+            (TouchPhase::Started, None) => {
+                self.primary.set(Some(finger));
+                vec![
+                    InputEvent::MouseMove(position.0, position.1),
+                    InputEvent::MouseDown { button, position },
+                ]
+            }
+            (TouchPhase::Moved, Some(primary)) if primary == finger => {
+                vec![InputEvent::MouseMove(position.0, position.1)]
+            }
+            (TouchPhase::Ended, Some(primary)) | (TouchPhase::Cancelled, Some(primary))
+                if primary == finger =>
+            {
+                self.primary.set(None);
+                vec![
+                    InputEvent::MouseMove(position.0, position.1),
+                    InputEvent::MouseUp { button, position },
+                ]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
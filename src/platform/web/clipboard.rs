@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use failure::Error;
+
+use input::{Event, InputEvent};
+use js;
+use js::clipboard::ClipboardHandler;
+
+use super::Context;
+
+// The Clipboard API is asynchronous and reading it may prompt the user, so the last text the page
+// knows of is cached. It is updated by paste and copy events and by reads the app asks for.
+#[derive(Default)]
+pub struct ClipboardState {
+    text: Option<String>,
+    pastes: Vec<Event>,
+}
+
+pub struct Clipboard(Rc<RefCell<ClipboardState>>);
+
+impl Clipboard {
+    pub fn new(context: &Context) -> Clipboard {
+        Clipboard(Rc::clone(&context.clipboard))
+    }
+
+    pub fn text(&self) -> Result<Option<String>, Error> {
+        Ok(self.0.borrow().text.clone())
+    }
+
+    pub fn request_text(&self) {
+        js::clipboard::read_clipboard();
+    }
+
+    pub fn set_text(&self, text: &str) -> Result<(), Error> {
+        self.0.borrow_mut().text = Some(text.into());
+        js::clipboard::write_clipboard(text);
+        Ok(())
+    }
+}
+
+pub fn init() -> Rc<RefCell<ClipboardState>> {
+    let state = Rc::new(RefCell::new(ClipboardState::default()));
+    let mut handler = ClipboardHandler::new();
+
+    let clipboard = Rc::clone(&state);
+    handler.set_text(move |text: String| {
+        clipboard.borrow_mut().text = Some(text);
+    });
+
+    let clipboard = Rc::clone(&state);
+    handler.set_paste(move |time, text: String| {
+        let mut clipboard = clipboard.borrow_mut();
+        clipboard.text = Some(text.clone());
+        let event = Event::new(None, InputEvent::Paste(text)).time(time / 1000.0);
+        clipboard.pastes.push(event);
+    });
+
+    js::clipboard::set_clipboard_handler(handler);
+    state
+}
+
+// The paste events since the last call
+pub fn events(state: &RefCell<ClipboardState>) -> Vec<Event> {
+    state.borrow_mut().pastes.drain(0..).collect()
+}
@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use input::{Event, GamepadAxis, GamepadButton, GamepadId, InputEvent};
+use js;
+use js::gamepad::GamepadHandler;
+
+// Indices of the browser's "standard" gamepad mapping, gamepads without it report their buttons
+// and axes in whatever order the device uses
+const LEFT_TRIGGER: u32 = 6;
+const RIGHT_TRIGGER: u32 = 7;
+
+pub struct Gamepads {
+    events: Rc<RefCell<Vec<InputEvent>>>,
+}
+
+impl Gamepads {
+    pub fn new() -> Gamepads {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        js::gamepad::set_gamepad_handler(gamepad_handler(&events));
+        Gamepads { events }
+    }
+
+    pub fn poll(&self) -> Vec<Event> {
+        js::gamepad::poll_gamepads();
+        let mut events = self.events.borrow_mut();
+        events.drain(0..).map(|e| Event::new(None, e)).collect()
+    }
+}
+
+fn gamepad_handler(gamepad_events: &Rc<RefCell<Vec<InputEvent>>>) -> GamepadHandler {
+    let mut handler = GamepadHandler::new();
+
+    let events = Rc::clone(gamepad_events);
+    handler.set_connected(move |gamepad| {
+        events
+            .borrow_mut()
+            .push(InputEvent::GamepadConnected(GamepadId(gamepad)));
+    });
+
+    let events = Rc::clone(gamepad_events);
+    handler.set_disconnected(move |gamepad| {
+        events
+            .borrow_mut()
+            .push(InputEvent::GamepadDisconnected(GamepadId(gamepad)));
+    });
+
+    let events = Rc::clone(gamepad_events);
+    handler.set_button(move |gamepad, button, pressed| {
+        let gamepad = GamepadId(gamepad);
+        if let Some(button) = to_button(button) {
+            events.borrow_mut().push(if pressed {
+                InputEvent::GamepadButtonDown { gamepad, button }
+            } else {
+                InputEvent::GamepadButtonUp { gamepad, button }
+            });
+        }
+    });
+
+    // the triggers are analog buttons in the browser and axes everywhere else
+    let events = Rc::clone(gamepad_events);
+    handler.set_button_value(move |gamepad, button, value| {
+        let axis = match button {
+            LEFT_TRIGGER => GamepadAxis::TriggerLeft,
+            RIGHT_TRIGGER => GamepadAxis::TriggerRight,
+            _ => return,
+        };
+        events.borrow_mut().push(InputEvent::GamepadAxisMotion {
+            gamepad: GamepadId(gamepad),
+            axis,
+            value,
+        });
+    });
+
+    let events = Rc::clone(gamepad_events);
+    handler.set_axis(move |gamepad, axis, value| {
+        if let Some(axis) = to_axis(axis) {
+            events.borrow_mut().push(InputEvent::GamepadAxisMotion {
+                gamepad: GamepadId(gamepad),
+                axis,
+                value,
+            });
+        }
+    });
+
+    handler
+}
+
+fn to_button(button: u32) -> Option<GamepadButton> {
+    Some(match button {
+        0 => GamepadButton::A,
+        1 => GamepadButton::B,
+        2 => GamepadButton::X,
+        3 => GamepadButton::Y,
+        4 => GamepadButton::LeftShoulder,
+        5 => GamepadButton::RightShoulder,
+        8 => GamepadButton::Back,
+        9 => GamepadButton::Start,
+        10 => GamepadButton::LeftStick,
+        11 => GamepadButton::RightStick,
+        12 => GamepadButton::DPadUp,
+        13 => GamepadButton::DPadDown,
+        14 => GamepadButton::DPadLeft,
+        15 => GamepadButton::DPadRight,
+        16 => GamepadButton::Guide,
+        _ => return None,
+    })
+}
+
+fn to_axis(axis: u32) -> Option<GamepadAxis> {
+    Some(match axis {
+        0 => GamepadAxis::LeftX,
+        1 => GamepadAxis::LeftY,
+        2 => GamepadAxis::RightX,
+        3 => GamepadAxis::RightY,
+        _ => return None,
+    })
+}
@@ -1,4 +1,13 @@
-use input::{Key, MouseButton};
+use input::{Key, Modifiers, MouseButton, ScanCode, TouchPhase};
+
+// KeyboardEvent.location for keys that exist on both sides of the keyboard
+const DOM_KEY_LOCATION_RIGHT: i32 = 2;
+
+// bits set by `modifier_bits` in window.js
+const SHIFT_BIT: u8 = 1;
+const CTRL_BIT: u8 = 2;
+const ALT_BIT: u8 = 4;
+const META_BIT: u8 = 8;
 
 pub fn to_mouse_button(button: i8) -> MouseButton {
     match button {
@@ -9,14 +18,164 @@ pub fn to_mouse_button(button: i8) -> MouseButton {
     }
 }
 
-pub fn to_key(key_code: i32) -> Key {
+pub fn to_touch_phase(phase: u8) -> TouchPhase {
+    match phase {
+        0 => TouchPhase::Started,
+        1 => TouchPhase::Moved,
+        2 => TouchPhase::Ended,
+        _ => TouchPhase::Cancelled,
+    }
+}
+
+pub fn to_modifiers(bits: u8) -> Modifiers {
+    Modifiers {
+        shift: bits & SHIFT_BIT != 0,
+        ctrl: bits & CTRL_BIT != 0,
+        alt: bits & ALT_BIT != 0,
+        gui: bits & META_BIT != 0,
+    }
+}
+
+// `code` is KeyboardEvent.code
+pub fn to_scancode(code: &str) -> ScanCode {
+    match code {
+        "KeyA" => ScanCode::A,
+        "KeyB" => ScanCode::B,
+        "KeyC" => ScanCode::C,
+        "KeyD" => ScanCode::D,
+        "KeyE" => ScanCode::E,
+        "KeyF" => ScanCode::F,
+        "KeyG" => ScanCode::G,
+        "KeyH" => ScanCode::H,
+        "KeyI" => ScanCode::I,
+        "KeyJ" => ScanCode::J,
+        "KeyK" => ScanCode::K,
+        "KeyL" => ScanCode::L,
+        "KeyM" => ScanCode::M,
+        "KeyN" => ScanCode::N,
+        "KeyO" => ScanCode::O,
+        "KeyP" => ScanCode::P,
+        "KeyQ" => ScanCode::Q,
+        "KeyR" => ScanCode::R,
+        "KeyS" => ScanCode::S,
+        "KeyT" => ScanCode::T,
+        "KeyU" => ScanCode::U,
+        "KeyV" => ScanCode::V,
+        "KeyW" => ScanCode::W,
+        "KeyX" => ScanCode::X,
+        "KeyY" => ScanCode::Y,
+        "KeyZ" => ScanCode::Z,
+        "Digit1" => ScanCode::Num1,
+        "Digit2" => ScanCode::Num2,
+        "Digit3" => ScanCode::Num3,
+        "Digit4" => ScanCode::Num4,
+        "Digit5" => ScanCode::Num5,
+        "Digit6" => ScanCode::Num6,
+        "Digit7" => ScanCode::Num7,
+        "Digit8" => ScanCode::Num8,
+        "Digit9" => ScanCode::Num9,
+        "Digit0" => ScanCode::Num0,
+        "Enter" => ScanCode::Return,
+        "Escape" => ScanCode::Escape,
+        "Backspace" => ScanCode::Backspace,
+        "Tab" => ScanCode::Tab,
+        "Space" => ScanCode::Space,
+        "Minus" => ScanCode::Minus,
+        "Equal" => ScanCode::Equals,
+        "BracketLeft" => ScanCode::LeftBracket,
+        "BracketRight" => ScanCode::RightBracket,
+        "Backslash" => ScanCode::Backslash,
+        "Semicolon" => ScanCode::Semicolon,
+        "Quote" => ScanCode::Apostrophe,
+        "Backquote" => ScanCode::Grave,
+        "Comma" => ScanCode::Comma,
+        "Period" => ScanCode::Period,
+        "Slash" => ScanCode::Slash,
+        "CapsLock" => ScanCode::CapsLock,
+        "F1" => ScanCode::F1,
+        "F2" => ScanCode::F2,
+        "F3" => ScanCode::F3,
+        "F4" => ScanCode::F4,
+        "F5" => ScanCode::F5,
+        "F6" => ScanCode::F6,
+        "F7" => ScanCode::F7,
+        "F8" => ScanCode::F8,
+        "F9" => ScanCode::F9,
+        "F10" => ScanCode::F10,
+        "F11" => ScanCode::F11,
+        "F12" => ScanCode::F12,
+        "PrintScreen" => ScanCode::PrintScreen,
+        "ScrollLock" => ScanCode::ScrollLock,
+        "Pause" => ScanCode::Pause,
+        "Insert" => ScanCode::Insert,
+        "Home" => ScanCode::Home,
+        "PageUp" => ScanCode::PageUp,
+        "Delete" => ScanCode::Delete,
+        "End" => ScanCode::End,
+        "PageDown" => ScanCode::PageDown,
+        "ArrowRight" => ScanCode::Right,
+        "ArrowLeft" => ScanCode::Left,
+        "ArrowDown" => ScanCode::Down,
+        "ArrowUp" => ScanCode::Up,
+        "NumLock" => ScanCode::NumLockClear,
+        "NumpadDivide" => ScanCode::KpDivide,
+        "NumpadMultiply" => ScanCode::KpMultiply,
+        "NumpadSubtract" => ScanCode::KpMinus,
+        "NumpadAdd" => ScanCode::KpPlus,
+        "NumpadEnter" => ScanCode::KpEnter,
+        "Numpad1" => ScanCode::Kp1,
+        "Numpad2" => ScanCode::Kp2,
+        "Numpad3" => ScanCode::Kp3,
+        "Numpad4" => ScanCode::Kp4,
+        "Numpad5" => ScanCode::Kp5,
+        "Numpad6" => ScanCode::Kp6,
+        "Numpad7" => ScanCode::Kp7,
+        "Numpad8" => ScanCode::Kp8,
+        "Numpad9" => ScanCode::Kp9,
+        "Numpad0" => ScanCode::Kp0,
+        "NumpadDecimal" => ScanCode::KpPeriod,
+        "IntlBackslash" => ScanCode::NonUsBackslash,
+        "ContextMenu" => ScanCode::Application,
+        "NumpadEqual" => ScanCode::KpEquals,
+        "F13" => ScanCode::F13,
+        "F14" => ScanCode::F14,
+        "F15" => ScanCode::F15,
+        "F16" => ScanCode::F16,
+        "F17" => ScanCode::F17,
+        "F18" => ScanCode::F18,
+        "F19" => ScanCode::F19,
+        "F20" => ScanCode::F20,
+        "F21" => ScanCode::F21,
+        "F22" => ScanCode::F22,
+        "F23" => ScanCode::F23,
+        "F24" => ScanCode::F24,
+        "ControlLeft" => ScanCode::LCtrl,
+        "ShiftLeft" => ScanCode::LShift,
+        "AltLeft" => ScanCode::LAlt,
+        "MetaLeft" => ScanCode::LGui,
+        "ControlRight" => ScanCode::RCtrl,
+        "ShiftRight" => ScanCode::RShift,
+        "AltRight" => ScanCode::RAlt,
+        "MetaRight" => ScanCode::RGui,
+        // older browsers name the meta keys after the OS key
+        "OSLeft" => ScanCode::LGui,
+        "OSRight" => ScanCode::RGui,
+        _ => ScanCode::Unknown,
+    }
+}
+
+pub fn to_key(key_code: i32, location: i32) -> Key {
+    let right = location == DOM_KEY_LOCATION_RIGHT;
     match key_code {
         8 => Key::Backspace,
         9 => Key::Tab,
         13 => Key::Return,
-        16 => Key::Shift,
-        17 => Key::Ctrl,
-        18 => Key::Alt,
+        16 if right => Key::RShift,
+        16 => Key::LShift,
+        17 if right => Key::RCtrl,
+        17 => Key::LCtrl,
+        18 if right => Key::RAlt,
+        18 => Key::LAlt,
         19 => Key::Pause,
         20 => Key::CapsLock,
         27 => Key::Escape,
@@ -67,8 +226,9 @@ pub fn to_key(key_code: i32) -> Key {
         88 => Key::X,
         89 => Key::Y,
         90 => Key::Z,
-        91 => Key::Application,
-        92 => Key::Application,
+        91 | 224 if right => Key::RGui,
+        91 | 224 => Key::LGui,
+        92 => Key::RGui,
         93 => Key::Select,
         96 => Key::Kp0,
         97 => Key::Kp1,
@@ -1,12 +1,14 @@
 pub mod audio;
+pub mod clipboard;
 mod console_writer;
+mod gamepad;
 mod input;
 pub mod rand;
 pub mod rendering;
 //pub mod websocket;
 pub mod window;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::io;
 use std::rc::Rc;
 
@@ -16,24 +18,32 @@ use js;
 use js::MainLoopCallback;
 
 use self::console_writer::ConsoleWriter;
-use input::Input;
-use window::WindowSettings;
+use app::ControlFlow;
+use input::Event;
+use window::{FramePacing, WindowSettings};
 
+use self::gamepad::Gamepads;
 use self::window::Window;
+use super::MAX_FRAME_DT;
 
 pub struct Context {
     windows: Rc<RefCell<Vec<Rc<Window>>>>,
+    frame_pacing: Rc<Cell<Option<FramePacing>>>,
+    window_count: u32,
+    clipboard: Rc<RefCell<clipboard::ClipboardState>>,
 }
 
 impl Context {
     pub fn window(&mut self, settings: WindowSettings) -> Result<Rc<Window>, Error> {
+        let frame_pacing = FramePacing::shared(self.frame_pacing.get(), settings.frame_pacing)?;
         let window = Rc::new(Window::new(self, settings)?);
+        self.frame_pacing.set(Some(frame_pacing));
         self.windows.borrow_mut().push(window.clone());
         Ok(window)
     }
 }
 
-pub fn init<F: FnOnce(Context) -> T, T: FnMut(f64, &Input) -> Result<(), Error> + 'static>(
+pub fn init<F: FnOnce(Context) -> T, T: FnMut(f64, Vec<Event>) -> ControlFlow + 'static>(
     app_factory: F,
 ) {
     js::bootstrap();
@@ -41,21 +51,43 @@ pub fn init<F: FnOnce(Context) -> T, T: FnMut(f64, &Input) -> Result<(), Error>
     io::set_print(Some(Box::new(ConsoleWriter::new())));
     io::set_panic(Some(Box::new(ConsoleWriter::new())));
 
-    let mut input = Input::new();
     let windows = Rc::new(RefCell::new(Vec::new()));
+    let frame_pacing = Rc::new(Cell::new(None));
+    let clipboard = clipboard::init();
     let context = Context {
         windows: Rc::clone(&windows),
+        frame_pacing: Rc::clone(&frame_pacing),
+        window_count: 0,
+        clipboard: Rc::clone(&clipboard),
     };
+    let gamepads = Gamepads::new();
     let mut main_loop = app_factory(context);
+    let mut last_frame = js::performance_now();
     js::set_main_loop(MainLoopCallback(Box::new(move || {
-        input.update(
-            windows
-                .borrow_mut()
-                .iter_mut()
-                .flat_map(|w| w.events())
-                .collect(),
-        );
-
-        main_loop(0.016, &input).unwrap();
+        let now = js::performance_now();
+        let frame_dt = (now - last_frame) / 1000.0;
+
+        // the browser always presents on its own refresh, vsync modes and uncapped all run on
+        // every animation frame and a capped rate skips animation frames until it is due
+        if let Some(FramePacing::Capped(fps)) = frame_pacing.get() {
+            // half a millisecond of slack to not miss a frame to timer jitter
+            if frame_dt < 1.0 / fps - 0.0005 {
+                return true;
+            }
+        }
+        last_frame = now;
+
+        // gamepads are polled, their changes are only seen at the start of the frame
+        let mut events: Vec<Event> = gamepads
+            .poll()
+            .into_iter()
+            .map(|e| e.time(now / 1000.0))
+            .collect();
+        events.extend(super::close_dropped_windows(&windows, now / 1000.0));
+        events.extend(windows.borrow_mut().iter_mut().flat_map(|w| w.events()));
+        events.extend(clipboard::events(&clipboard));
+
+        // returning false stops the animation loop and drops the app along with its windows
+        main_loop(frame_dt.min(MAX_FRAME_DT), events) == ControlFlow::Continue
     })));
 }
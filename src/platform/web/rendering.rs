@@ -1,10 +1,15 @@
+use std::collections::HashMap;
+
 use failure::Error;
 
 use js::webgl;
 use js::webgl::types::*;
 
 use assets::Image;
-use rendering::{TextureFiltering, Vertex, VertexAttributeType};
+use rendering::{
+    BlendEquation, BlendFactor, CullFace, DrawRange, Index, IndexType, Primitive, RenderState,
+    TextureFiltering, Vertex, VertexAttributeType,
+};
 
 struct VertexShader {
     handle: webgl::Shader,
@@ -65,34 +70,93 @@ impl Drop for VertexBuffer {
     }
 }
 
+pub struct IndexBuffer(webgl::Buffer);
+
+impl Drop for IndexBuffer {
+    fn drop(&mut self) {
+        webgl::gl_delete_buffer(&self.0);
+    }
+}
+
 #[derive(Clone)]
 pub enum Uniform {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
     Vec2((f32, f32)),
+    Vec3((f32, f32, f32)),
+    Vec4((f32, f32, f32, f32)),
+    Mat2([f32; 4]),
+    Mat3([f32; 9]),
+    Mat4([f32; 16]),
+    FloatArray(Vec<f32>),
+    IntArray(Vec<i32>),
+    Vec2Array(Vec<(f32, f32)>),
+    Vec3Array(Vec<(f32, f32, f32)>),
+    Vec4Array(Vec<(f32, f32, f32, f32)>),
     Texture(Texture),
 }
 
 pub struct Program {
-    uniforms: Vec<(String, Uniform)>,
+    uniforms: HashMap<String, Uniform>,
+    // locations of the program's active uniforms and attributes, looked up once after linking
+    uniform_locations: HashMap<String, webgl::UniformLocation>,
+    attrib_locations: HashMap<String, GLint>,
     handle: webgl::Program,
 }
 
 impl Program {
     fn new(vertex_shader: VertexShader, frag_shader: FragmentShader) -> Result<Program, Error> {
+        let handle = link_program(&vertex_shader, &frag_shader)?;
+
+        let uniform_count = webgl::gl_get_program_parameter(&handle, webgl::ACTIVE_UNIFORMS);
+        let uniform_locations = (0..uniform_count as GLuint)
+            .map(|i| {
+                let name = webgl::gl_get_active_uniform_name(&handle, i);
+                let location = webgl::gl_get_uniform_location(&handle, &name);
+                (variable_name(&name), location)
+            })
+            .collect();
+        let attrib_count = webgl::gl_get_program_parameter(&handle, webgl::ACTIVE_ATTRIBUTES);
+        let attrib_locations = (0..attrib_count as GLuint)
+            .map(|i| {
+                let name = webgl::gl_get_active_attrib_name(&handle, i);
+                let location = webgl::gl_get_attrib_location(&handle, &name);
+                (variable_name(&name), location)
+            })
+            .collect();
+
         Ok(Program {
-            uniforms: Vec::new(),
-            handle: link_program(&vertex_shader, &frag_shader)?,
+            uniforms: HashMap::new(),
+            uniform_locations,
+            attrib_locations,
+            handle,
         })
     }
     fn handle<'a>(&'a self) -> &webgl::Program {
         &self.handle
     }
 
-    pub fn set_uniform(&mut self, name: &str, uniform: Uniform) {
-        self.uniforms.push((name.into(), uniform));
+    // Uniforms the shader compiler optimized out are not active and can't be set either
+    pub fn set_uniform(&mut self, name: &str, uniform: Uniform) -> Result<(), Error> {
+        if !self.uniform_locations.contains_key(name) {
+            return Err(format_err!("no active uniform named {} in program", name));
+        }
+        self.uniforms.insert(name.into(), uniform);
+        Ok(())
     }
-    pub fn uniforms(&self) -> impl Iterator<Item = &(String, Uniform)> {
+    pub fn uniforms(&self) -> impl Iterator<Item = (&String, &Uniform)> {
         self.uniforms.iter()
     }
+    fn uniform_location(&self, name: &str) -> &webgl::UniformLocation {
+        &self.uniform_locations[name]
+    }
+    // `None` for attributes the shader compiler optimized out
+    fn attrib_location(&self, name: &str) -> Option<GLuint> {
+        self.attrib_locations
+            .get(name)
+            .map(|&location| location as GLuint)
+    }
 }
 
 impl Drop for Program {
@@ -167,6 +231,9 @@ pub fn create_vertex_buffer() -> Result<VertexBuffer, Error> {
 
     Ok(vbo)
 }
+pub fn create_index_buffer() -> Result<IndexBuffer, Error> {
+    Ok(IndexBuffer(webgl::gl_create_buffer()))
+}
 pub fn create_program(vs: &str, fs: &str) -> Result<Program, Error> {
     let vs = VertexShader::new(vs)?;
     let fs = FragmentShader::new(fs)?;
@@ -184,15 +251,7 @@ pub fn create_texture(
     Ok(Texture::new(size, filtering))
 }
 
-pub fn render_vertices<V: Vertex>(
-    vertex_buffer: &VertexBuffer,
-    program: &Program,
-    vertices: &Vec<V>,
-) -> Result<(), Error> {
-    webgl::gl_blend_func(webgl::SRC_ALPHA, webgl::ONE_MINUS_SRC_ALPHA);
-    webgl::gl_enable(webgl::BLEND);
-
-    // push vertex data
+pub fn upload_vertices<V: Vertex>(vertex_buffer: &VertexBuffer, vertices: &Vec<V>) {
     webgl::gl_bind_buffer(webgl::ARRAY_BUFFER, vertex_buffer.handle());
     unsafe {
         let data = ::std::slice::from_raw_parts(
@@ -201,19 +260,192 @@ pub fn render_vertices<V: Vertex>(
         );
         webgl::gl_buffer_data(webgl::ARRAY_BUFFER, data, webgl::STATIC_DRAW);
     }
+}
+
+pub fn upload_indices<I: Index>(
+    _vertex_buffer: &VertexBuffer,
+    index_buffer: &IndexBuffer,
+    indices: &Vec<I>,
+) {
+    webgl::gl_bind_buffer(webgl::ELEMENT_ARRAY_BUFFER, &index_buffer.0);
+    unsafe {
+        let data = ::std::slice::from_raw_parts(
+            indices.as_ptr() as *const u8,
+            indices.len() * I::index_type().size(),
+        );
+        webgl::gl_buffer_data(webgl::ELEMENT_ARRAY_BUFFER, data, webgl::STATIC_DRAW);
+    }
+}
+
+pub fn draw_vertices<V: Vertex>(
+    vertex_buffer: &VertexBuffer,
+    program: &Program,
+    state: &RenderState,
+    range: DrawRange,
+) -> Result<(), Error> {
+    setup_draw::<V>(vertex_buffer, program, state)?;
+    webgl::gl_draw_arrays(
+        to_gl_primitive(range.primitive),
+        range.first as GLint,
+        range.count as GLsizei,
+    );
+
+    Ok(())
+}
+
+pub fn draw_indexed<V: Vertex>(
+    vertex_buffer: &VertexBuffer,
+    index_buffer: &IndexBuffer,
+    program: &Program,
+    state: &RenderState,
+    index_type: IndexType,
+    range: DrawRange,
+) -> Result<(), Error> {
+    // WebGL 1 only has 32 bit indices through an extension
+    let gl_index_type = match index_type {
+        IndexType::UnsignedShort => webgl::UNSIGNED_SHORT,
+        IndexType::UnsignedInt => {
+            if !webgl::gl_element_index_uint_supported() {
+                return Err(format_err!("32 bit indices are not supported"));
+            }
+            webgl::UNSIGNED_INT
+        }
+    };
+
+    setup_draw::<V>(vertex_buffer, program, state)?;
+    webgl::gl_bind_buffer(webgl::ELEMENT_ARRAY_BUFFER, &index_buffer.0);
+    webgl::gl_draw_elements(
+        to_gl_primitive(range.primitive),
+        range.count as GLsizei,
+        gl_index_type,
+        (range.first * index_type.size()) as GLintptr,
+    );
+
+    Ok(())
+}
+
+fn apply_render_state(state: &RenderState) {
+    match state.blend.funcs() {
+        Some((color, alpha)) => {
+            webgl::gl_enable(webgl::BLEND);
+            webgl::gl_blend_func_separate(
+                to_gl_blend_factor(color.src),
+                to_gl_blend_factor(color.dst),
+                to_gl_blend_factor(alpha.src),
+                to_gl_blend_factor(alpha.dst),
+            );
+            webgl::gl_blend_equation_separate(
+                to_gl_blend_equation(color.equation),
+                to_gl_blend_equation(alpha.equation),
+            );
+        }
+        None => webgl::gl_disable(webgl::BLEND),
+    }
+
+    let mask = state.color_mask;
+    webgl::gl_color_mask(mask.r, mask.g, mask.b, mask.a);
+
+    match state.cull {
+        Some(face) => {
+            webgl::gl_enable(webgl::CULL_FACE);
+            webgl::gl_cull_face(match face {
+                CullFace::Front => webgl::FRONT,
+                CullFace::Back => webgl::BACK,
+                CullFace::FrontAndBack => webgl::FRONT_AND_BACK,
+            });
+        }
+        None => webgl::gl_disable(webgl::CULL_FACE),
+    }
+}
+
+fn to_gl_blend_factor(factor: BlendFactor) -> GLenum {
+    match factor {
+        BlendFactor::Zero => webgl::ZERO,
+        BlendFactor::One => webgl::ONE,
+        BlendFactor::SrcColor => webgl::SRC_COLOR,
+        BlendFactor::OneMinusSrcColor => webgl::ONE_MINUS_SRC_COLOR,
+        BlendFactor::DstColor => webgl::DST_COLOR,
+        BlendFactor::OneMinusDstColor => webgl::ONE_MINUS_DST_COLOR,
+        BlendFactor::SrcAlpha => webgl::SRC_ALPHA,
+        BlendFactor::OneMinusSrcAlpha => webgl::ONE_MINUS_SRC_ALPHA,
+        BlendFactor::DstAlpha => webgl::DST_ALPHA,
+        BlendFactor::OneMinusDstAlpha => webgl::ONE_MINUS_DST_ALPHA,
+    }
+}
+
+fn to_gl_blend_equation(equation: BlendEquation) -> GLenum {
+    match equation {
+        BlendEquation::Add => webgl::FUNC_ADD,
+        BlendEquation::Subtract => webgl::FUNC_SUBTRACT,
+        BlendEquation::ReverseSubtract => webgl::FUNC_REVERSE_SUBTRACT,
+    }
+}
+
+fn to_gl_primitive(primitive: Primitive) -> GLenum {
+    match primitive {
+        Primitive::Points => webgl::POINTS,
+        Primitive::Lines => webgl::LINES,
+        Primitive::LineStrip => webgl::LINE_STRIP,
+        Primitive::LineLoop => webgl::LINE_LOOP,
+        Primitive::Triangles => webgl::TRIANGLES,
+        Primitive::TriangleStrip => webgl::TRIANGLE_STRIP,
+        Primitive::TriangleFan => webgl::TRIANGLE_FAN,
+    }
+}
+
+// Binds the vertex buffer, program and uniforms a draw call of the buffer's vertices needs
+fn setup_draw<V: Vertex>(
+    vertex_buffer: &VertexBuffer,
+    program: &Program,
+    state: &RenderState,
+) -> Result<(), Error> {
+    apply_render_state(state);
+
+    webgl::gl_bind_buffer(webgl::ARRAY_BUFFER, vertex_buffer.handle());
 
     webgl::gl_use_program(program.handle());
 
     // set uniforms
     let mut texture_index = 0;
-    for &(ref name, ref uniform) in program.uniforms() {
-        let attr = webgl::gl_get_uniform_location(program.handle(), name);
+    for (name, uniform) in program.uniforms() {
+        let attr = program.uniform_location(name);
         match uniform {
-            &Uniform::Vec2(gl_vec2) => webgl::gl_uniform2f(&attr, gl_vec2.0, gl_vec2.1),
+            &Uniform::Float(v) => webgl::gl_uniform1f(attr, v),
+            &Uniform::Int(v) => webgl::gl_uniform1i(attr, v),
+            &Uniform::Bool(v) => webgl::gl_uniform1i(attr, v as GLint),
+            &Uniform::Vec2(gl_vec2) => webgl::gl_uniform2f(attr, gl_vec2.0, gl_vec2.1),
+            &Uniform::Vec3((x, y, z)) => webgl::gl_uniform3f(attr, x, y, z),
+            &Uniform::Vec4((x, y, z, w)) => webgl::gl_uniform4f(attr, x, y, z, w),
+            &Uniform::Mat2(ref m) => webgl::gl_uniform_matrix2fv(attr, false, m),
+            &Uniform::Mat3(ref m) => webgl::gl_uniform_matrix3fv(attr, false, m),
+            &Uniform::Mat4(ref m) => webgl::gl_uniform_matrix4fv(attr, false, m),
+            &Uniform::FloatArray(ref v) => webgl::gl_uniform1fv(attr, v),
+            &Uniform::IntArray(ref v) => webgl::gl_uniform1iv(attr, v),
+            &Uniform::Vec2Array(ref v) => {
+                let mut flat = Vec::with_capacity(v.len() * 2);
+                for &(x, y) in v {
+                    flat.extend_from_slice(&[x, y]);
+                }
+                webgl::gl_uniform2fv(attr, &flat)
+            }
+            &Uniform::Vec3Array(ref v) => {
+                let mut flat = Vec::with_capacity(v.len() * 3);
+                for &(x, y, z) in v {
+                    flat.extend_from_slice(&[x, y, z]);
+                }
+                webgl::gl_uniform3fv(attr, &flat)
+            }
+            &Uniform::Vec4Array(ref v) => {
+                let mut flat = Vec::with_capacity(v.len() * 4);
+                for &(x, y, z, w) in v {
+                    flat.extend_from_slice(&[x, y, z, w]);
+                }
+                webgl::gl_uniform4fv(attr, &flat)
+            }
             &Uniform::Texture(ref gl_texture) => {
                 webgl::gl_active_texture(webgl::TEXTURE0 + texture_index);
                 webgl::gl_bind_texture(webgl::TEXTURE_2D, gl_texture.handle());
-                webgl::gl_uniform1i(&attr, texture_index as GLint);
+                webgl::gl_uniform1i(attr, texture_index as GLint);
                 texture_index += 1;
             }
         }
@@ -222,48 +454,43 @@ pub fn render_vertices<V: Vertex>(
     // define vertex format
     let mut step = 0;
     for (attr_name, attr_count, attr_type) in V::attributes() {
-        let attr = webgl::gl_get_attrib_location(program.handle(), &attr_name);
-        if attr < 0 {
-            return Err(format_err!(
-                "could not find location of attribute {}",
-                attr_name
-            ));
-        }
-        let attr = attr as u32;
-        webgl::gl_enable_vertex_attrib_array(attr as u32);
-        match attr_type {
-            VertexAttributeType::Float => {
-                webgl::gl_vertex_attrib_pointer(
-                    attr,
-                    attr_count as GLsizei,
-                    webgl::FLOAT,
-                    false,
-                    V::stride() as GLsizei,
-                    step,
-                );
-            }
-            VertexAttributeType::Unsigned => {
-                webgl::gl_vertex_attrib_pointer(
-                    attr,
-                    attr_count as GLsizei,
-                    webgl::UNSIGNED_INT,
-                    false,
-                    V::stride() as GLsizei,
-                    step,
-                );
+        // attributes missing from the program are skipped, their data is still in the stride
+        if let Some(attr) = program.attrib_location(&attr_name) {
+            webgl::gl_enable_vertex_attrib_array(attr);
+            match attr_type {
+                VertexAttributeType::Float => {
+                    webgl::gl_vertex_attrib_pointer(
+                        attr,
+                        attr_count as GLsizei,
+                        webgl::FLOAT,
+                        false,
+                        V::stride() as GLsizei,
+                        step,
+                    );
+                }
+                VertexAttributeType::Unsigned => {
+                    webgl::gl_vertex_attrib_pointer(
+                        attr,
+                        attr_count as GLsizei,
+                        webgl::UNSIGNED_INT,
+                        false,
+                        V::stride() as GLsizei,
+                        step,
+                    );
+                }
             }
         }
 
         step += (attr_count * attr_type.size()) as GLsizei;
     }
 
-    webgl::gl_draw_arrays(webgl::TRIANGLES, 0, vertices.len() as GLsizei);
-
     Ok(())
 }
 
 pub fn clear(color: Option<(f32, f32, f32, f32)>) {
     let (r, g, b, a) = color.unwrap_or((0.0, 0.0, 0.0, 1.0));
+    // clearing obeys the color mask, which draws leave set
+    webgl::gl_color_mask(true, true, true, true);
     webgl::gl_clear_color(r, g, b, a);
     webgl::gl_clear(webgl::COLOR_BUFFER_BIT);
 }
@@ -295,3 +522,13 @@ fn link_program(vs: &VertexShader, fs: &FragmentShader) -> Result<webgl::Program
     }
     Ok(program)
 }
+
+// Arrays are reported by their first element, "lights[0]", but set by their plain name. Members
+// of arrays of structs, "lights[0].color", keep their index.
+fn variable_name(name: &str) -> String {
+    if name.ends_with("[0]") {
+        name[..name.len() - 3].into()
+    } else {
+        name.into()
+    }
+}
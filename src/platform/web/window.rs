@@ -1,43 +1,164 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use failure::Error;
 
-use input::InputEvent;
+use input::{Event, FingerId, InputEvent};
 use js;
+use js::webgl;
 use js::window::{CanvasWindow, InputHandler as JsInputHandler};
-use window::WindowSettings;
+use math::Vec2;
+use platform::touch::TouchMouse;
+use window::{Cursor, Fullscreen, WindowId, WindowSettings};
 
-use super::input::{to_key, to_mouse_button};
+use super::input::{to_key, to_modifiers, to_mouse_button, to_scancode, to_touch_phase};
 use super::Context;
 
 pub struct GLContext(js::window::GLContext);
 
-type InputEvents = Rc<RefCell<Vec<InputEvent>>>;
+// events with the time they happened at, in milliseconds
+type InputEvents = Rc<RefCell<Vec<(f64, InputEvent)>>>;
 
 pub struct Window {
+    id: WindowId,
     js_window: CanvasWindow,
     input_events: InputEvents,
+    viewport_size: Cell<(i32, i32)>,
+    touch_mouse: Option<TouchMouse>,
 }
 
 impl Window {
-    pub fn new(_: &mut Context, settings: WindowSettings) -> Result<Window, Error> {
+    pub fn new(context: &mut Context, settings: WindowSettings) -> Result<Window, Error> {
         let input_events = Rc::new(RefCell::new(Vec::new()));
         let handler = input_handler(&input_events);
-        let WindowSettings { canvas_id, .. } = settings;
+        let WindowSettings {
+            canvas_id,
+            size,
+            resizable,
+            fullscreen,
+            min_size,
+            touch_as_mouse,
+            ..
+        } = settings;
 
         let canvas_id =
             canvas_id.ok_or_else(|| format_err!("missing canvas id in WindowSettings"))?;
+        let js_window = js::window::create_canvas_window(&canvas_id, handler);
+        if let Some(size) = size {
+            js::window::set_canvas_window_size(&js_window, size.x, size.y);
+        }
+        if let Some(min_size) = min_size {
+            js::window::set_canvas_window_min_size(&js_window, min_size.x, min_size.y);
+        }
+        js::window::set_canvas_window_resizable(&js_window, resizable);
+        // browsers only allow fullscreen from a user gesture, so this may be refused
+        js::window::set_canvas_window_fullscreen(&js_window, fullscreen.is_some());
+        // the initial size is not a resize
+        input_events.borrow_mut().clear();
+
+        context.window_count += 1;
+        let id = WindowId(context.window_count);
         Ok(Window {
-            js_window: js::window::create_canvas_window(&canvas_id, handler),
+            id,
+            js_window,
             input_events,
+            viewport_size: Cell::new((0, 0)),
+            touch_mouse: if touch_as_mouse {
+                Some(TouchMouse::new(id))
+            } else {
+                None
+            },
         })
     }
 
-    pub fn events(&self) -> impl Iterator<Item = InputEvent> {
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    pub fn size(&self) -> Vec2<u32> {
+        Vec2::new(
+            js::window::canvas_window_width(&self.js_window),
+            js::window::canvas_window_height(&self.js_window),
+        )
+    }
+
+    pub fn physical_size(&self) -> Vec2<u32> {
+        Vec2::new(
+            js::window::canvas_window_drawable_width(&self.js_window),
+            js::window::canvas_window_drawable_height(&self.js_window),
+        )
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        js::window::canvas_window_scale_factor(&self.js_window)
+    }
+
+    pub fn set_size(&self, size: Vec2<u32>) -> Result<(), Error> {
+        js::window::set_canvas_window_size(&self.js_window, size.x, size.y);
+        Ok(())
+    }
+
+    // the browser has a single fullscreen mode, exclusive and borderless both map to it
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) -> Result<(), Error> {
+        js::window::set_canvas_window_fullscreen(&self.js_window, fullscreen.is_some());
+        Ok(())
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = Event> {
+        let id = self.id;
         let mut events = self.input_events.borrow_mut();
-        let events = events.drain(0..).collect::<Vec<InputEvent>>();
-        events.into_iter()
+        let events = events
+            .drain(0..)
+            .map(|(time, e)| Event::new(Some(id), e).time(time / 1000.0))
+            .collect();
+        self.synthesize_mouse_events(events).into_iter()
+    }
+
+    pub fn synthesize_mouse_events(&self, events: Vec<Event>) -> Vec<Event> {
+        match self.touch_mouse {
+            Some(ref touch_mouse) => touch_mouse.synthesize(events),
+            None => events,
+        }
+    }
+
+    pub fn set_cursor_visible(&self, visible: bool) {
+        js::window::set_canvas_window_cursor_visible(&self.js_window, visible);
+    }
+
+    // browsers can only confine the cursor through pointer lock, which also hides it
+    pub fn set_cursor_grab(&self, _: bool) -> Result<(), Error> {
+        Err(format_err!(
+            "cursor grab is not supported, use relative mouse mode"
+        ))
+    }
+
+    // pointer lock is granted asynchronously and only after a click on the canvas
+    pub fn set_relative_mouse_mode(&self, relative: bool) -> Result<(), Error> {
+        js::window::set_canvas_window_relative_mouse_mode(&self.js_window, relative);
+        Ok(())
+    }
+
+    pub fn set_cursor(&self, cursor: Option<&Cursor>) -> Result<(), Error> {
+        match cursor {
+            Some(cursor) => js::window::set_canvas_window_cursor(
+                &self.js_window,
+                &cursor.image.data,
+                cursor.image.width,
+                cursor.image.height,
+                cursor.hotspot.x,
+                cursor.hotspot.y,
+            ),
+            None => js::window::reset_canvas_window_cursor(&self.js_window),
+        }
+        Ok(())
+    }
+
+    pub fn start_text_input(&self) {
+        js::window::start_canvas_window_text_input(&self.js_window);
+    }
+
+    pub fn stop_text_input(&self) {
+        js::window::stop_canvas_window_text_input(&self.js_window);
     }
 
     pub fn gl_create_context(&self) -> GLContext {
@@ -46,41 +167,159 @@ impl Window {
 
     pub fn gl_set_current(&self, gl_context: &GLContext) {
         js::window::gl_set_current_context(&gl_context.0);
+
+        // keep the viewport covering the canvas as it gets resized
+        let drawable_size = (
+            webgl::gl_drawing_buffer_width(),
+            webgl::gl_drawing_buffer_height(),
+        );
+        if drawable_size != self.viewport_size.get() {
+            webgl::gl_viewport(0, 0, drawable_size.0, drawable_size.1);
+            self.viewport_size.set(drawable_size);
+        }
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        js::window::delete_canvas_window(&self.js_window);
     }
 }
 
-fn input_handler(input_events: &Rc<RefCell<Vec<InputEvent>>>) -> JsInputHandler {
+fn input_handler(input_events: &InputEvents) -> JsInputHandler {
     let mut handler = JsInputHandler::new();
 
     let events = Rc::clone(input_events);
-    handler.set_mouse_move(move |x, y| {
-        events.borrow_mut().push(InputEvent::MouseMove(x, y));
+    handler.set_mouse_move(move |time, x, y| {
+        events
+            .borrow_mut()
+            .push((time, InputEvent::MouseMove(x, y)));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_mouse_motion(move |time, x, y| {
+        events
+            .borrow_mut()
+            .push((time, InputEvent::MouseMotion(x, y)));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_mouse_down(move |time, button, x, y| {
+        events.borrow_mut().push((
+            time,
+            InputEvent::MouseDown {
+                button: to_mouse_button(button),
+                position: (x, y),
+            },
+        ));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_mouse_up(move |time, button, x, y| {
+        events.borrow_mut().push((
+            time,
+            InputEvent::MouseUp {
+                button: to_mouse_button(button),
+                position: (x, y),
+            },
+        ));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_mouse_wheel(move |time, x, y| {
+        events
+            .borrow_mut()
+            .push((time, InputEvent::MouseWheel { x, y }));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_touch(move |time, pointer_id, phase, x, y, pressure| {
+        events.borrow_mut().push((
+            time,
+            InputEvent::Touch {
+                finger: FingerId(pointer_id as i64),
+                phase: to_touch_phase(phase),
+                position: (x, y),
+                pressure,
+            },
+        ));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_key_down(move |time, key, location, code, modifiers, repeat| {
+        events.borrow_mut().push((
+            time,
+            InputEvent::KeyDown {
+                key: to_key(key, location),
+                scancode: to_scancode(&code),
+                modifiers: to_modifiers(modifiers),
+                repeat,
+            },
+        ));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_key_up(move |time, key, location, code, modifiers, _| {
+        events.borrow_mut().push((
+            time,
+            InputEvent::KeyUp {
+                key: to_key(key, location),
+                scancode: to_scancode(&code),
+                modifiers: to_modifiers(modifiers),
+            },
+        ));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_text_input(move |time, text| {
+        events
+            .borrow_mut()
+            .push((time, InputEvent::TextInput(text)));
+    });
+
+    let events = Rc::clone(input_events);
+    handler.set_text_editing(move |time, text, start, length| {
+        events.borrow_mut().push((
+            time,
+            InputEvent::TextEditing {
+                text,
+                start,
+                length,
+            },
+        ));
     });
 
     let events = Rc::clone(input_events);
-    handler.set_mouse_down(move |button, x, y| {
-        events.borrow_mut().push(InputEvent::MouseDown {
-            button: to_mouse_button(button),
-            position: (x, y),
-        });
+    handler.set_focus(move |time, focused| {
+        events
+            .borrow_mut()
+            .push((time, InputEvent::Focused(focused)));
     });
 
     let events = Rc::clone(input_events);
-    handler.set_mouse_up(move |button, x, y| {
-        events.borrow_mut().push(InputEvent::MouseUp {
-            button: to_mouse_button(button),
-            position: (x, y),
-        });
+    handler.set_visibility(move |time, visible| {
+        events.borrow_mut().push((
+            time,
+            if visible {
+                InputEvent::Resumed
+            } else {
+                InputEvent::Suspended
+            },
+        ));
     });
 
     let events = Rc::clone(input_events);
-    handler.set_key_down(move |key| {
-        events.borrow_mut().push(InputEvent::KeyDown(to_key(key)));
+    handler.set_resize(move |time, width, height| {
+        events
+            .borrow_mut()
+            .push((time, InputEvent::Resized(width, height)));
     });
 
     let events = Rc::clone(input_events);
-    handler.set_key_up(move |key| {
-        events.borrow_mut().push(InputEvent::KeyUp(to_key(key)));
+    handler.set_scale_factor_changed(move |time, scale_factor| {
+        events
+            .borrow_mut()
+            .push((time, InputEvent::ScaleFactorChanged(scale_factor)));
     });
 
     handler
@@ -1,8 +1,10 @@
+use std::cell::Cell;
 use std::marker::PhantomData;
 
 use failure::Error;
 
 use assets::Image;
+use math::{Vec2, Vec3};
 use platform::rendering as render_impl;
 
 #[derive(Debug, Clone, Copy)]
@@ -26,6 +28,212 @@ impl VertexAttributeType {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendEquation {
+    // src * src_factor + dst * dst_factor
+    Add,
+    // src * src_factor - dst * dst_factor
+    Subtract,
+    // dst * dst_factor - src * src_factor
+    ReverseSubtract,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlendFunc {
+    pub src: BlendFactor,
+    pub dst: BlendFactor,
+    pub equation: BlendEquation,
+}
+
+impl BlendFunc {
+    pub fn new(src: BlendFactor, dst: BlendFactor) -> BlendFunc {
+        BlendFunc {
+            src,
+            dst,
+            equation: BlendEquation::Add,
+        }
+    }
+
+    pub fn equation(mut self, equation: BlendEquation) -> Self {
+        self.equation = equation;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    // overwrite the target
+    None,
+    // straight alpha
+    Alpha,
+    // colors already multiplied by their alpha
+    PremultipliedAlpha,
+    // add to the target, for glows and particles
+    Additive,
+    // darken the target by the source color
+    Multiply,
+    // separate functions for the color and alpha channels
+    Custom { color: BlendFunc, alpha: BlendFunc },
+}
+
+impl BlendMode {
+    // The color and alpha blend functions of the mode, `None` when blending is off
+    pub fn funcs(self) -> Option<(BlendFunc, BlendFunc)> {
+        use self::BlendFactor::*;
+
+        let both = |src, dst| Some((BlendFunc::new(src, dst), BlendFunc::new(src, dst)));
+        match self {
+            BlendMode::None => None,
+            BlendMode::Alpha => both(SrcAlpha, OneMinusSrcAlpha),
+            BlendMode::PremultipliedAlpha => both(One, OneMinusSrcAlpha),
+            BlendMode::Additive => both(SrcAlpha, One),
+            BlendMode::Multiply => both(DstColor, Zero),
+            BlendMode::Custom { color, alpha } => Some((color, alpha)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColorMask {
+    pub r: bool,
+    pub g: bool,
+    pub b: bool,
+    pub a: bool,
+}
+
+impl Default for ColorMask {
+    fn default() -> ColorMask {
+        ColorMask {
+            r: true,
+            g: true,
+            b: true,
+            a: true,
+        }
+    }
+}
+
+// Faces are told apart by winding, counter-clockwise triangles are front facing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CullFace {
+    Front,
+    Back,
+    FrontAndBack,
+}
+
+// Fixed-function state of a single draw. The default alpha blends, writes all channels and
+// draws both faces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderState {
+    pub blend: BlendMode,
+    pub color_mask: ColorMask,
+    pub cull: Option<CullFace>,
+}
+
+impl Default for RenderState {
+    fn default() -> RenderState {
+        RenderState {
+            blend: BlendMode::Alpha,
+            color_mask: ColorMask::default(),
+            cull: None,
+        }
+    }
+}
+
+impl RenderState {
+    pub fn new() -> RenderState {
+        RenderState::default()
+    }
+
+    pub fn blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+    pub fn color_mask(mut self, color_mask: ColorMask) -> Self {
+        self.color_mask = color_mask;
+        self
+    }
+    pub fn cull(mut self, cull: Option<CullFace>) -> Self {
+        self.cull = cull;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+// Which of a buffer's elements to draw, `count` of them starting at `first`, and what to draw
+// them as
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrawRange {
+    pub primitive: Primitive,
+    pub first: usize,
+    pub count: usize,
+}
+
+impl DrawRange {
+    pub fn new(primitive: Primitive, first: usize, count: usize) -> DrawRange {
+        DrawRange {
+            primitive,
+            first,
+            count,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexType {
+    UnsignedShort,
+    UnsignedInt,
+}
+
+impl IndexType {
+    pub fn size(self) -> usize {
+        match self {
+            IndexType::UnsignedShort => 2,
+            IndexType::UnsignedInt => 4,
+        }
+    }
+}
+
+// Element type of an index buffer, 16 bit indices address up to 65536 vertices at half the size
+pub trait Index: Copy + Into<u32> {
+    fn index_type() -> IndexType;
+}
+
+impl Index for u16 {
+    fn index_type() -> IndexType {
+        IndexType::UnsignedShort
+    }
+}
+
+impl Index for u32 {
+    fn index_type() -> IndexType {
+        IndexType::UnsignedInt
+    }
+}
+
 pub trait Vertex {
     fn stride() -> usize {
         Self::attributes()
@@ -35,15 +243,80 @@ pub trait Vertex {
     fn attributes() -> Vec<(String, usize, VertexAttributeType)>;
 }
 
+#[derive(Clone)]
 pub enum Uniform {
+    Float(f32),
+    Int(i32),
+    // set as an int, GLSL has no bool uniform upload of its own
+    Bool(bool),
     Vec2((f32, f32)),
+    Vec3((f32, f32, f32)),
+    Vec4((f32, f32, f32, f32)),
+    // matrices are column-major, the layout GLSL expects
+    Mat2([f32; 4]),
+    Mat3([f32; 9]),
+    Mat4([f32; 16]),
+    FloatArray(Vec<f32>),
+    IntArray(Vec<i32>),
+    Vec2Array(Vec<(f32, f32)>),
+    Vec3Array(Vec<(f32, f32, f32)>),
+    Vec4Array(Vec<(f32, f32, f32, f32)>),
     Texture(Texture),
 }
 
+impl From<f32> for Uniform {
+    fn from(v: f32) -> Self {
+        Uniform::Float(v)
+    }
+}
+
+impl From<i32> for Uniform {
+    fn from(v: i32) -> Self {
+        Uniform::Int(v)
+    }
+}
+
+impl From<bool> for Uniform {
+    fn from(v: bool) -> Self {
+        Uniform::Bool(v)
+    }
+}
+
+impl From<Vec2<f32>> for Uniform {
+    fn from(v: Vec2<f32>) -> Self {
+        Uniform::Vec2((v.x, v.y))
+    }
+}
+
+impl From<Vec3<f32>> for Uniform {
+    fn from(v: Vec3<f32>) -> Self {
+        Uniform::Vec3((v.x, v.y, v.z))
+    }
+}
+
+impl From<Texture> for Uniform {
+    fn from(t: Texture) -> Self {
+        Uniform::Texture(t)
+    }
+}
+
 impl From<render_impl::Uniform> for Uniform {
     fn from(uniform: render_impl::Uniform) -> Self {
         match uniform {
+            render_impl::Uniform::Float(v) => Uniform::Float(v),
+            render_impl::Uniform::Int(v) => Uniform::Int(v),
+            render_impl::Uniform::Bool(v) => Uniform::Bool(v),
             render_impl::Uniform::Vec2(v) => Uniform::Vec2(v),
+            render_impl::Uniform::Vec3(v) => Uniform::Vec3(v),
+            render_impl::Uniform::Vec4(v) => Uniform::Vec4(v),
+            render_impl::Uniform::Mat2(v) => Uniform::Mat2(v),
+            render_impl::Uniform::Mat3(v) => Uniform::Mat3(v),
+            render_impl::Uniform::Mat4(v) => Uniform::Mat4(v),
+            render_impl::Uniform::FloatArray(v) => Uniform::FloatArray(v),
+            render_impl::Uniform::IntArray(v) => Uniform::IntArray(v),
+            render_impl::Uniform::Vec2Array(v) => Uniform::Vec2Array(v),
+            render_impl::Uniform::Vec3Array(v) => Uniform::Vec3Array(v),
+            render_impl::Uniform::Vec4Array(v) => Uniform::Vec4Array(v),
             render_impl::Uniform::Texture(t) => Uniform::Texture(Texture(t)),
         }
     }
@@ -52,13 +325,57 @@ impl From<render_impl::Uniform> for Uniform {
 impl Into<render_impl::Uniform> for Uniform {
     fn into(self) -> render_impl::Uniform {
         match self {
+            Uniform::Float(v) => render_impl::Uniform::Float(v),
+            Uniform::Int(v) => render_impl::Uniform::Int(v),
+            Uniform::Bool(v) => render_impl::Uniform::Bool(v),
             Uniform::Vec2(v) => render_impl::Uniform::Vec2(v),
+            Uniform::Vec3(v) => render_impl::Uniform::Vec3(v),
+            Uniform::Vec4(v) => render_impl::Uniform::Vec4(v),
+            Uniform::Mat2(v) => render_impl::Uniform::Mat2(v),
+            Uniform::Mat3(v) => render_impl::Uniform::Mat3(v),
+            Uniform::Mat4(v) => render_impl::Uniform::Mat4(v),
+            Uniform::FloatArray(v) => render_impl::Uniform::FloatArray(v),
+            Uniform::IntArray(v) => render_impl::Uniform::IntArray(v),
+            Uniform::Vec2Array(v) => render_impl::Uniform::Vec2Array(v),
+            Uniform::Vec3Array(v) => render_impl::Uniform::Vec3Array(v),
+            Uniform::Vec4Array(v) => render_impl::Uniform::Vec4Array(v),
             Uniform::Texture(t) => render_impl::Uniform::Texture(t.0),
         }
     }
 }
 
-pub struct VertexBuffer(render_impl::VertexBuffer);
+// Buffers keep what was last uploaded to them, so they can be drawn any number of times and in
+// parts
+pub struct VertexBuffer {
+    inner: render_impl::VertexBuffer,
+    len: Cell<usize>,
+}
+
+impl VertexBuffer {
+    // Number of vertices last uploaded
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct IndexBuffer {
+    inner: render_impl::IndexBuffer,
+    len: Cell<usize>,
+    index_type: Cell<IndexType>,
+    max_index: Cell<Option<usize>>,
+}
+
+impl IndexBuffer {
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
 
 pub struct Program<V: Vertex> {
     inner: render_impl::Program,
@@ -66,8 +383,10 @@ pub struct Program<V: Vertex> {
 }
 
 impl<V: Vertex> Program<V> {
-    pub fn set_uniform(&mut self, name: &str, uniform: Uniform) {
-        self.inner.set_uniform(name, uniform.into())
+    // Replaces the uniform's previous value, fails if the program has no active uniform by
+    // that name
+    pub fn set_uniform<U: Into<Uniform>>(&mut self, name: &str, uniform: U) -> Result<(), Error> {
+        self.inner.set_uniform(name, uniform.into().into())
     }
     pub fn uniforms<'a>(&'a self) -> impl Iterator<Item = (String, Uniform)> + 'a {
         self.inner
@@ -113,7 +432,21 @@ impl<'a> Renderer<'a> {
     pub fn create_vertex_buffer(&self) -> Result<VertexBuffer, Error> {
         self.target.make_current();
 
-        Ok(VertexBuffer(render_impl::create_vertex_buffer()?))
+        Ok(VertexBuffer {
+            inner: render_impl::create_vertex_buffer()?,
+            len: Cell::new(0),
+        })
+    }
+
+    pub fn create_index_buffer(&self) -> Result<IndexBuffer, Error> {
+        self.target.make_current();
+
+        Ok(IndexBuffer {
+            inner: render_impl::create_index_buffer()?,
+            len: Cell::new(0),
+            index_type: Cell::new(IndexType::UnsignedShort),
+            max_index: Cell::new(None),
+        })
     }
 
     pub fn create_program<V: Vertex>(&self, vs: &str, fs: &str) -> Result<Program<V>, Error> {
@@ -135,16 +468,110 @@ impl<'a> Renderer<'a> {
         Ok(Texture(render_impl::create_texture(size, filtering)?))
     }
 
+    pub fn upload_vertices<V: Vertex>(&self, vertex_buffer: &VertexBuffer, vertices: &Vec<V>) {
+        self.target.make_current();
+
+        render_impl::upload_vertices(&vertex_buffer.inner, vertices);
+        vertex_buffer.len.set(vertices.len());
+    }
+
+    // Index buffers are tied to the vertex buffer they index into
+    pub fn upload_indices<I: Index>(
+        &self,
+        vertex_buffer: &VertexBuffer,
+        index_buffer: &IndexBuffer,
+        indices: &Vec<I>,
+    ) {
+        self.target.make_current();
+
+        render_impl::upload_indices(&vertex_buffer.inner, &index_buffer.inner, indices);
+        index_buffer.len.set(indices.len());
+        index_buffer.index_type.set(I::index_type());
+        let max_index = indices.iter().map(|&i| i.into() as usize).max();
+        index_buffer.max_index.set(max_index);
+    }
+
+    // Draws the buffer's vertices in `range`
+    pub fn draw_vertices<V: Vertex>(
+        &self,
+        vertex_buffer: &VertexBuffer,
+        program: &Program<V>,
+        state: &RenderState,
+        range: DrawRange,
+    ) -> Result<(), Error> {
+        check_range(range.first, range.count, vertex_buffer.len())?;
+        self.target.make_current();
+
+        render_impl::draw_vertices::<V>(&vertex_buffer.inner, &program.inner, state, range)
+    }
+
+    // Draws the vertices of the indices in `range`. Every uploaded index has to be within the
+    // vertex buffer, not only the drawn ones.
+    pub fn draw_indexed<V: Vertex>(
+        &self,
+        vertex_buffer: &VertexBuffer,
+        index_buffer: &IndexBuffer,
+        program: &Program<V>,
+        state: &RenderState,
+        range: DrawRange,
+    ) -> Result<(), Error> {
+        check_range(range.first, range.count, index_buffer.len())?;
+        if let Some(max_index) = index_buffer.max_index.get() {
+            if max_index >= vertex_buffer.len() {
+                return Err(format_err!(
+                    "index {} out of bounds of vertex buffer with {} vertices",
+                    max_index,
+                    vertex_buffer.len()
+                ));
+            }
+        }
+        self.target.make_current();
+
+        render_impl::draw_indexed::<V>(
+            &vertex_buffer.inner,
+            &index_buffer.inner,
+            &program.inner,
+            state,
+            index_buffer.index_type.get(),
+            range,
+        )
+    }
+
+    // Uploads and draws alpha blended triangles in one go
     pub fn render_vertices<V: Vertex>(
         &self,
         vertex_buffer: &VertexBuffer,
         program: &Program<V>,
         vertices: &Vec<V>,
     ) -> Result<(), Error> {
-        self.target.make_current();
+        self.upload_vertices(vertex_buffer, vertices);
+        self.draw_vertices(
+            vertex_buffer,
+            program,
+            &RenderState::default(),
+            DrawRange::new(Primitive::Triangles, 0, vertices.len()),
+        )
+    }
 
-        render_impl::render_vertices(&vertex_buffer.0, &program.inner, vertices)?;
-        Ok(())
+    // Draws triangles from `indices` into `vertices`, so vertices shared between triangles
+    // are only stored once
+    pub fn render_indexed<V: Vertex, I: Index>(
+        &self,
+        vertex_buffer: &VertexBuffer,
+        index_buffer: &IndexBuffer,
+        program: &Program<V>,
+        vertices: &Vec<V>,
+        indices: &Vec<I>,
+    ) -> Result<(), Error> {
+        self.upload_vertices(vertex_buffer, vertices);
+        self.upload_indices(vertex_buffer, index_buffer, indices);
+        self.draw_indexed(
+            vertex_buffer,
+            index_buffer,
+            program,
+            &RenderState::default(),
+            DrawRange::new(Primitive::Triangles, 0, indices.len()),
+        )
     }
 
     pub fn clear(&self, color: Option<(f32, f32, f32, f32)>) {
@@ -153,3 +580,187 @@ impl<'a> Renderer<'a> {
         render_impl::clear(color);
     }
 }
+
+fn check_range(first: usize, count: usize, len: usize) -> Result<(), Error> {
+    match first.checked_add(count) {
+        Some(end) if end <= len => Ok(()),
+        _ => Err(format_err!(
+            "draw range of {} elements from {} out of bounds of buffer with {} elements",
+            count,
+            first,
+            len
+        )),
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+#[repr(C)]
+struct Point {
+    pos: Vec2<f32>,
+}
+
+#[cfg(all(test, feature = "headless"))]
+impl Vertex for Point {
+    fn attributes() -> Vec<(String, usize, VertexAttributeType)> {
+        vec![("pos".into(), 2, VertexAttributeType::Float)]
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+fn quad() -> Vec<Point> {
+    [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        .iter()
+        .map(|&(x, y)| Point {
+            pos: Vec2::new(x, y),
+        })
+        .collect()
+}
+
+// Runs `draw` once with the renderer of a small headless window and returns its draw calls
+#[cfg(all(test, feature = "headless"))]
+fn draw_frame<F>(draw: F) -> Vec<::headless::DrawCall>
+where
+    F: FnOnce(&Renderer) -> Result<(), Error> + 'static,
+{
+    use headless::{run, take_draw_calls, HeadlessSettings};
+    use input::Input;
+    use window::WindowSettings;
+
+    run(HeadlessSettings::new(), |mut context| {
+        context.set_error_handler(|e| panic!("{}", e));
+        let window = context
+            .window(WindowSettings::new().size(Vec2::new(16, 16)))
+            .unwrap();
+        let mut draw = Some(draw);
+        move |_, _: &Input| match draw.take() {
+            Some(draw) => draw(&window.renderer()),
+            None => Ok(()),
+        }
+    });
+    take_draw_calls()
+}
+
+#[cfg(feature = "headless")]
+#[test]
+fn indexed_draw() {
+    let calls = draw_frame(|renderer| {
+        let vertex_buffer = renderer.create_vertex_buffer()?;
+        let index_buffer = renderer.create_index_buffer()?;
+        let program = renderer.create_program::<Point>("void main() {}", "void main() {}")?;
+        let indices = vec![0u16, 1, 2, 2, 3, 0];
+        renderer.render_indexed(&vertex_buffer, &index_buffer, &program, &quad(), &indices)?;
+        // indices past the last vertex are refused
+        let past_end = vec![0u16, 4];
+        assert!(renderer
+            .render_indexed(&vertex_buffer, &index_buffer, &program, &quad(), &past_end)
+            .is_err());
+        Ok(())
+    });
+
+    assert_eq!(calls.len(), 1);
+    assert_eq!((calls[0].vertex_count, calls[0].index_count), (4, Some(6)));
+    assert_eq!(calls[0].vertex_stride, ::std::mem::size_of::<Point>());
+}
+
+#[cfg(feature = "headless")]
+#[test]
+fn draw_ranges() {
+    let calls = draw_frame(|renderer| {
+        let vertex_buffer = renderer.create_vertex_buffer()?;
+        let program = renderer.create_program::<Point>("void main() {}", "void main() {}")?;
+        renderer.upload_vertices(&vertex_buffer, &quad());
+
+        // the uploaded vertices stay in the buffer for more draws
+        let state = RenderState::default();
+        let draw = |primitive, first, count| {
+            let range = DrawRange::new(primitive, first, count);
+            renderer.draw_vertices(&vertex_buffer, &program, &state, range)
+        };
+        draw(Primitive::LineStrip, 1, 3)?;
+        draw(Primitive::Points, 0, 4)?;
+        assert!(draw(Primitive::Points, 2, 3).is_err());
+        assert!(draw(Primitive::Points, 1, usize::MAX).is_err());
+        Ok(())
+    });
+
+    assert_eq!(calls.len(), 2);
+    assert_eq!(
+        (calls[0].primitive, calls[0].vertex_count),
+        (Primitive::LineStrip, 3)
+    );
+    assert_eq!(
+        (calls[1].primitive, calls[1].vertex_count),
+        (Primitive::Points, 4)
+    );
+}
+
+#[cfg(feature = "headless")]
+#[test]
+fn uniform_types() {
+    draw_frame(|renderer| {
+        let vs = "uniform mat4 transform;\nuniform vec3 tint, lights[4];\nvoid main() {}";
+        let fs = "uniform bool enabled;\nvoid main() {}";
+        let mut program = renderer.create_program::<Point>(vs, fs)?;
+        let mut identity = [0.0; 16];
+        for i in 0..4 {
+            identity[i * 5] = 1.0;
+        }
+        program.set_uniform("transform", Uniform::Mat4(identity))?;
+        program.set_uniform("tint", Vec3::new(1.0, 0.5, 0.0))?;
+        program.set_uniform("lights", Uniform::Vec3Array(vec![(0.0, 0.0, 0.0); 4]))?;
+        program.set_uniform("enabled", true)?;
+
+        assert_eq!(program.uniforms().count(), 4);
+        for (name, uniform) in program.uniforms() {
+            match (name.as_str(), uniform) {
+                ("transform", Uniform::Mat4(m)) => assert_eq!(m, identity),
+                ("tint", Uniform::Vec3(v)) => assert_eq!(v, (1.0, 0.5, 0.0)),
+                ("lights", Uniform::Vec3Array(v)) => assert_eq!(v.len(), 4),
+                ("enabled", Uniform::Bool(b)) => assert!(b),
+                _ => panic!("unexpected uniform {}", name),
+            }
+        }
+        Ok(())
+    });
+}
+
+#[cfg(feature = "headless")]
+#[test]
+fn uniform_replacement() {
+    let calls = draw_frame(|renderer| {
+        let vertex_buffer = renderer.create_vertex_buffer()?;
+        let vs = "uniform float time;\nvoid main() {}";
+        let mut program = renderer.create_program::<Point>(vs, "void main() {}")?;
+        program.set_uniform("time", 0.25)?;
+        program.set_uniform("time", 0.5)?;
+        renderer.render_vertices(&vertex_buffer, &program, &quad())?;
+        Ok(())
+    });
+
+    // set uniforms are replaced, not added again
+    assert_eq!(calls[0].uniforms, vec!["time"]);
+}
+
+#[cfg(feature = "headless")]
+#[test]
+fn render_state() {
+    let calls = draw_frame(|renderer| {
+        let vertex_buffer = renderer.create_vertex_buffer()?;
+        let program = renderer.create_program::<Point>("void main() {}", "void main() {}")?;
+        renderer.render_vertices(&vertex_buffer, &program, &quad())?;
+        let additive = RenderState::new()
+            .blend(BlendMode::Additive)
+            .cull(Some(CullFace::Back));
+        renderer.draw_vertices(
+            &vertex_buffer,
+            &program,
+            &additive,
+            DrawRange::new(Primitive::TriangleFan, 0, 4),
+        )?;
+        Ok(())
+    });
+
+    assert_eq!(calls[0].state, RenderState::default());
+    assert_eq!(calls[1].state.blend, BlendMode::Additive);
+    assert_eq!(calls[1].state.cull, Some(CullFace::Back));
+}
@@ -0,0 +1,152 @@
+use failure::Error;
+
+use app::{App, ControlFlow};
+use input::{Event, Input};
+use math::Vec2;
+
+// Steps run per frame at most unless changed with `FixedTimestep::max_steps`
+pub const DEFAULT_MAX_STEPS: u32 = 5;
+
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    tick_rate: f64,
+    max_steps: u32,
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    pub fn new(tick_rate: f64) -> Result<FixedTimestep, Error> {
+        if !tick_rate.is_finite() || tick_rate <= 0.0 {
+            return Err(format_err!(
+                "tick rate must be positive and finite, got {}",
+                tick_rate
+            ));
+        }
+        Ok(FixedTimestep {
+            tick_rate,
+            max_steps: DEFAULT_MAX_STEPS,
+            accumulator: 0.0,
+        })
+    }
+
+    pub fn max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn dt(&self) -> f64 {
+        1.0 / self.tick_rate
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.accumulator / self.dt()
+    }
+
+    // Accumulates frame time and returns the number of fixed steps to run for this frame
+    pub fn advance(&mut self, frame_dt: f64) -> u32 {
+        let dt = self.dt();
+        self.accumulator += frame_dt;
+
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < self.max_steps {
+            self.accumulator -= dt;
+            steps += 1;
+        }
+        // drop whatever we can't catch up on instead of spiraling further behind
+        if self.accumulator >= dt {
+            self.accumulator %= dt;
+        }
+        steps
+    }
+}
+
+// Wraps an app so its update runs at the timestep's tick rate, rendering with the fraction of
+// a tick left over in the accumulator
+pub struct FixedStep<A: App> {
+    timestep: FixedTimestep,
+    app: A,
+    // input as seen by the last fixed step, so presses and releases that happen on frames
+    // without any steps are reported on the next step that runs
+    last_step_input: Option<Input>,
+    // events of frames since the last step, handed to the next step that runs
+    pending_events: Vec<Event>,
+}
+
+impl<A: App> FixedStep<A> {
+    pub fn new(timestep: FixedTimestep, app: A) -> FixedStep<A> {
+        FixedStep {
+            timestep,
+            app,
+            last_step_input: None,
+            pending_events: Vec::new(),
+        }
+    }
+}
+
+impl<A: App> App for FixedStep<A> {
+    fn start(&mut self) -> Result<(), Error> {
+        self.app.start()
+    }
+    fn update(&mut self, frame_dt: f64, input: &Input) -> Result<ControlFlow, Error> {
+        let steps = self.timestep.advance(frame_dt);
+        self.pending_events.extend(input.events().cloned());
+        if steps == 0 {
+            return Ok(ControlFlow::Continue);
+        }
+
+        let mut step_input = match self.last_step_input {
+            Some(ref last) => input.with_previous(last),
+            None => input.clone(),
+        };
+        step_input.set_events(self.pending_events.drain(..).collect());
+        for _ in 0..steps {
+            if self.app.update(self.timestep.dt(), &step_input)? == ControlFlow::Exit {
+                return Ok(ControlFlow::Exit);
+            }
+            step_input = step_input.with_previous(&step_input);
+            step_input.set_events(Vec::new());
+        }
+        self.last_step_input = Some(step_input);
+        Ok(ControlFlow::Continue)
+    }
+    fn render(&mut self, _alpha: f64) -> Result<(), Error> {
+        self.app.render(self.timestep.alpha())
+    }
+    fn on_resize(&mut self, size: Vec2<u32>) {
+        self.app.on_resize(size)
+    }
+    fn on_scale_factor_changed(&mut self, scale_factor: f64) {
+        self.app.on_scale_factor_changed(scale_factor)
+    }
+    fn on_focus_changed(&mut self, focused: bool) {
+        self.app.on_focus_changed(focused)
+    }
+    fn on_suspend(&mut self) {
+        self.app.on_suspend()
+    }
+    fn on_resume(&mut self) {
+        self.app.on_resume()
+    }
+    fn on_exit(&mut self) {
+        self.app.on_exit()
+    }
+}
+
+#[test]
+fn fixed_timestep() {
+    assert!(FixedTimestep::new(0.0).is_err());
+    assert!(FixedTimestep::new(f64::NAN).is_err());
+
+    let mut timestep = FixedTimestep::new(10.0).unwrap().max_steps(3);
+
+    assert_eq!(timestep.advance(0.05), 0);
+    assert!((timestep.alpha() - 0.5).abs() < 1e-9);
+    assert_eq!(timestep.advance(0.06), 1);
+    assert!((timestep.alpha() - 0.1).abs() < 1e-9);
+    assert_eq!(timestep.advance(0.2), 2);
+
+    // falling far behind is capped and the backlog dropped
+    assert_eq!(timestep.advance(1.0), 3);
+    assert!(timestep.alpha() < 1.0);
+    assert_eq!(timestep.advance(0.0), 0);
+}
@@ -2,6 +2,7 @@ use std::rc::Rc;
 
 use failure::Error;
 
+use assets::Image;
 use math::Vec2;
 use platform::window as window_impl;
 
@@ -9,11 +10,78 @@ use rendering::{RenderTarget, Renderer};
 
 use super::PlatformContext;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowId(pub u32);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FramePacing {
+    #[default]
+    VSync,
+    // vsync that lets late frames tear instead of waiting a whole extra refresh, falls back to
+    // regular vsync where unsupported
+    AdaptiveVSync,
+    // vsync off, sleeping between frames to hold the given frames per second
+    Capped(f64),
+    Uncapped,
+}
+
+impl FramePacing {
+    // All windows share one frame pacing, the first window decides it and later windows can
+    // leave it unset or have to ask for the same
+    pub(crate) fn shared(
+        current: Option<FramePacing>,
+        requested: Option<FramePacing>,
+    ) -> Result<FramePacing, Error> {
+        match (current, requested) {
+            (Some(current), Some(requested)) if current != requested => Err(format_err!(
+                "frame pacing {:?} differs from the {:?} shared by all windows",
+                requested,
+                current
+            )),
+            (Some(current), _) => Ok(current),
+            (None, requested) => Ok(requested.unwrap_or_default()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fullscreen {
+    // switches the display mode to the window size
+    Exclusive,
+    // covers the whole display at its current resolution
+    Borderless,
+}
+
+// A custom mouse cursor, `hotspot` is the pixel of the image that points at the mouse position
+pub struct Cursor {
+    pub image: Image,
+    pub hotspot: Vec2<u32>,
+}
+
+impl Cursor {
+    // `image` has to be RGBA with 8 bits per channel
+    pub fn new(image: Image, hotspot: Vec2<u32>) -> Result<Cursor, Error> {
+        if image.data.len() != (image.width * image.height * 4) as usize {
+            return Err(format_err!("cursor image is not 8 bit RGBA"));
+        }
+        if hotspot.x >= image.width || hotspot.y >= image.height {
+            return Err(format_err!("cursor hotspot is outside of the image"));
+        }
+        Ok(Cursor { image, hotspot })
+    }
+}
+
 #[derive(Default)]
 pub struct WindowSettings {
     pub title: Option<String>,
     pub size: Option<Vec2<u32>>,
     pub canvas_id: Option<String>,
+    // frame pacing is shared by all windows, see `FramePacing::shared`
+    pub frame_pacing: Option<FramePacing>,
+    pub resizable: bool,
+    pub fullscreen: Option<Fullscreen>,
+    pub min_size: Option<Vec2<u32>>,
+    pub touch_as_mouse: bool,
 }
 
 impl WindowSettings {
@@ -33,6 +101,27 @@ impl WindowSettings {
         self.canvas_id = Some(id);
         self
     }
+    pub fn frame_pacing(mut self, frame_pacing: FramePacing) -> Self {
+        self.frame_pacing = Some(frame_pacing);
+        self
+    }
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+    pub fn fullscreen(mut self, fullscreen: Fullscreen) -> Self {
+        self.fullscreen = Some(fullscreen);
+        self
+    }
+    pub fn min_size(mut self, v: Vec2<u32>) -> Self {
+        self.min_size = Some(v);
+        self
+    }
+    // Also send mouse events for the first finger touching the window
+    pub fn touch_as_mouse(mut self, touch_as_mouse: bool) -> Self {
+        self.touch_as_mouse = touch_as_mouse;
+        self
+    }
 }
 
 pub struct GLContext(window_impl::GLContext);
@@ -44,6 +133,14 @@ pub struct Window {
 
 impl Window {
     pub fn new(context: &mut PlatformContext, settings: WindowSettings) -> Result<Window, Error> {
+        if let Some(FramePacing::Capped(fps)) = settings.frame_pacing {
+            if fps.is_nan() || fps <= 0.0 {
+                return Err(format_err!(
+                    "capped frame rate must be positive, got {}",
+                    fps
+                ));
+            }
+        }
         let window = context.0.window(settings)?;
         let gl_context = Some(GLContext(window.gl_create_context()));
         Ok(Window {
@@ -52,6 +149,63 @@ impl Window {
         })
     }
 
+    pub fn id(&self) -> WindowId {
+        self.inner.id()
+    }
+
+    // Size in logical units, which are scaled by `scale_factor` on high-DPI displays
+    pub fn size(&self) -> Vec2<u32> {
+        self.inner.size()
+    }
+
+    // Size of the drawable in pixels
+    pub fn physical_size(&self) -> Vec2<u32> {
+        self.inner.physical_size()
+    }
+
+    // Physical pixels per logical unit
+    pub fn scale_factor(&self) -> f64 {
+        self.inner.scale_factor()
+    }
+
+    // `size` is in logical units
+    pub fn set_size(&self, size: Vec2<u32>) -> Result<(), Error> {
+        self.inner.set_size(size)
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) -> Result<(), Error> {
+        self.inner.set_fullscreen(fullscreen)
+    }
+
+    pub fn set_cursor_visible(&self, visible: bool) {
+        self.inner.set_cursor_visible(visible)
+    }
+
+    // Confines the cursor to the window
+    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), Error> {
+        self.inner.set_cursor_grab(grab)
+    }
+
+    // Hides and locks the cursor in place, the mouse then only reports `MouseMotion` deltas
+    pub fn set_relative_mouse_mode(&self, relative: bool) -> Result<(), Error> {
+        self.inner.set_relative_mouse_mode(relative)
+    }
+
+    // `None` goes back to the default cursor
+    pub fn set_cursor(&self, cursor: Option<&Cursor>) -> Result<(), Error> {
+        self.inner.set_cursor(cursor)
+    }
+
+    // Starts sending `TextInput` and `TextEditing` events, bringing up the on-screen keyboard or
+    // IME where there is one. Text input is off until this is called.
+    pub fn start_text_input(&self) {
+        self.inner.start_text_input()
+    }
+
+    pub fn stop_text_input(&self) {
+        self.inner.stop_text_input()
+    }
+
     pub fn renderer<'a>(&'a self) -> Renderer<'a> {
         Renderer::new(self)
     }